@@ -1,5 +1,159 @@
 extern crate napi_build;
 
+use std::io::Read;
+
+// Postcode, street and city of an address that must resolve in every data release
+const KNOWN_KEY: &str = "1012AB|Stationsplein|Amsterdam";
+
+// House number of that address
+const KNOWN_HOUSE_NUMBER: u32 = 1;
+
 fn main() {
     napi_build::setup();
+
+    println!("cargo:rerun-if-changed=postcode_data.br");
+    if std::env::var_os("CARGO_FEATURE_NO_EMBEDDED_DATA").is_none() {
+        validate_data("postcode_data.br");
+    }
+}
+
+// Fail the build on a corrupt or truncated data file instead of at the first lookup
+fn validate_data(path: &str) {
+    let compressed =
+        std::fs::read(path).unwrap_or_else(|e| panic!("Could not read {}: {}", path, e));
+    let mut decompressed_data = Vec::new();
+    brotli::Decompressor::new(compressed.as_slice(), 4096)
+        .read_to_end(&mut decompressed_data)
+        .unwrap_or_else(|e| panic!("{} is not valid brotli data: {}", path, e));
+
+    let (fst_bytes, shards) = split_payload(&decompressed_data, path);
+    let fst_map = fst::Map::new(fst_bytes)
+        .unwrap_or_else(|e| panic!("{} contains an invalid FST: {}", path, e));
+    let value = fst_map
+        .get(KNOWN_KEY)
+        .unwrap_or_else(|| panic!("{} does not contain {}", path, KNOWN_KEY));
+    let offset = (value & ((1 << 56) - 1)) as usize;
+    let house_data = shards
+        .get((value >> 56) as usize)
+        .unwrap_or_else(|| panic!("{} has no shard for {}", path, KNOWN_KEY));
+    let numbers = house_data
+        .get(offset..)
+        .map(decode_house_numbers)
+        .unwrap_or_default();
+    assert!(
+        numbers.contains(&KNOWN_HOUSE_NUMBER),
+        "{} does not have house number {} for {}",
+        path,
+        KNOWN_HOUSE_NUMBER,
+        KNOWN_KEY
+    );
+}
+
+// Decode a house-number block as the crate's `HouseNumberIter` does: a `u16`
+// count whose top bit announces an encoding tag byte (0 = delta, 1 = runs),
+// stopping early at the end of the data or a malformed entry
+fn decode_house_numbers(block: &[u8]) -> Vec<u32> {
+    let u16_at = |pos: usize| {
+        block
+            .get(pos..pos + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as u32)
+    };
+    let Some(header) = u16_at(0) else {
+        return Vec::new();
+    };
+    let count = (header & 0x7FFF) as usize;
+    let (runs, mut pos) = match (header & 0x8000 != 0, block.get(2)) {
+        (false, _) => (false, 2),
+        (true, Some(0)) => (false, 3),
+        (true, Some(1)) => (true, 3),
+        (true, _) => return Vec::new(),
+    };
+
+    let mut numbers: Vec<u32> = Vec::with_capacity(count);
+    while numbers.len() < count {
+        if runs {
+            let (Some(start), Some(length)) = (u16_at(pos), u16_at(pos + 2)) else {
+                break;
+            };
+            pos += 4;
+            if length == 0 {
+                break;
+            }
+            numbers.extend((start..start + length).take(count - numbers.len()));
+        } else {
+            let next = match numbers.last() {
+                None => u16_at(pos).inspect(|_| pos += 2),
+                Some(&last) => {
+                    let delta = match block.get(pos) {
+                        Some(0) => u16_at(pos + 1).inspect(|_| pos += 3),
+                        Some(&delta) => Some(delta as u32).inspect(|_| pos += 1),
+                        None => None,
+                    };
+                    delta.and_then(|delta| last.checked_add(delta))
+                }
+            };
+            match next {
+                Some(number) => numbers.push(number),
+                None => break,
+            }
+        }
+    }
+    numbers
+}
+
+// Legacy `[fst_len][house_len]` or versioned `PRST` header, as parsed by the crate
+fn split_payload<'a>(payload: &'a [u8], path: &str) -> (&'a [u8], Vec<&'a [u8]>) {
+    let u32_at =
+        |pos: usize| u32::from_le_bytes(payload[pos..pos + 4].try_into().unwrap()) as usize;
+    let u64_at =
+        |pos: usize| u64::from_le_bytes(payload[pos..pos + 8].try_into().unwrap()) as usize;
+    let (fst_len, shard_lens, kinds_len, mut pos) = if payload.starts_with(b"PRST") {
+        assert!(payload.len() >= 20, "{} is missing its header", path);
+        let version = u32_at(4);
+        assert!(
+            version == 2 || version == 3,
+            "{} has an unsupported format version",
+            path
+        );
+        let shard_count = u32_at(16);
+        let shards_end = 20 + 8 * shard_count;
+        // Version 3 appends the length of the kinds section to the header
+        let header_len = if version == 3 {
+            shards_end + 8
+        } else {
+            shards_end
+        };
+        assert!(
+            payload.len() >= header_len,
+            "{} is missing its header",
+            path
+        );
+        let shard_lens: Vec<usize> = (0..shard_count).map(|i| u64_at(20 + 8 * i)).collect();
+        let kinds_len = if version == 3 { u64_at(shards_end) } else { 0 };
+        (u64_at(8), shard_lens, kinds_len, header_len)
+    } else {
+        assert!(payload.len() >= 16, "{} is missing its header", path);
+        (u64_at(0), vec![u64_at(8)], 0, 16)
+    };
+    // Trailing bytes after the last section are allowed, as at runtime
+    let declared = pos + fst_len + shard_lens.iter().sum::<usize>() + kinds_len;
+    assert!(
+        declared <= payload.len(),
+        "{} header declares {} bytes but the payload has {}",
+        path,
+        declared,
+        payload.len()
+    );
+
+    let fst_bytes = &payload[pos..pos + fst_len];
+    pos += fst_len;
+    let shards = shard_lens
+        .into_iter()
+        .map(|len| {
+            let shard = &payload[pos..pos + len];
+            pos += len;
+            shard
+        })
+        .collect();
+    (fst_bytes, shards)
 }
@@ -1,16 +1,412 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+/**
+ * Make `from` an alias of the city `to` in the city filters
+ *
+ * Both names are case-insensitive and `to` should be the name as stored in
+ * the data. Aliases resolve one level deep; a later alias for the same name
+ * replaces the earlier one, built-in aliases included.
+ */
+export declare function addCityAlias(from: string, to: string): void
+
+/**
+ * Expand a postcode into all of its house numbers in one call
+ *
+ * Names follow the configured output casing, like lookup results. Returns
+ * `None` for an unknown postcode.
+ */
+export declare function addressesInPostcode(postcode: string): PostcodeAddresses | null
+
+/** Verdict of `validate_address`, one flag per check */
+export interface AddressValidation {
+  /** The postcode has at least one address */
+  postcodeExists: boolean
+  /** Some street of the postcode has the house number */
+  houseNumberExists: boolean
+  /** The postcode has a street named `expected_straat` */
+  streetMatches: boolean
+  /** The postcode lies (partly) in `expected_woonplaats` */
+  cityMatches: boolean
+  /** A single address has the house number, street and city together */
+  valid: boolean
+}
+
+/** What `lookup` does when several streets of a postcode have the number */
+export declare enum AmbiguityPolicy {
+  /** Return the first matching street in key order */
+  FirstMatch = 'FirstMatch',
+  /** Fail with an `AmbiguousAddress` error */
+  Error = 'Error',
+  /** Return the matching street with the most house numbers */
+  MostNumbers = 'MostNumbers'
+}
+
+/**
+ * List every distinct city a postcode belongs to, sorted
+ *
+ * Unknown postcodes give an empty list.
+ */
+export declare function citiesForPostcode(postcode: string): Array<string>
+
+/**
+ * Resolve the city of each postcode, in input order
+ *
+ * Each city is the `woonplaats` of the postcode's first street, as returned
+ * by `lookup` with house number 0, or `None` for an unknown postcode.
+ */
+export declare function citiesForPostcodes(postcodes: Array<string>): Array<string | null>
+
+/** Casing applied to city (and optionally street) names in lookup results */
+export declare enum CityCase {
+  /** Names exactly as stored in the dataset */
+  AsStored = 'AsStored',
+  /** Dutch-aware title case, e.g. "Bergen op Zoom" and "'s-Gravenhage" */
+  TitleCase = 'TitleCase',
+  /** All capitals */
+  UpperCase = 'UpperCase'
+}
+
+/** Compression of a data file or buffer */
+export declare enum Codec {
+  /** Brotli, the format of the embedded data */
+  Brotli = 'Brotli',
+  /** Gzip, available in builds with the `gzip` feature */
+  Gzip = 'Gzip'
+}
+
+/**
+ * Check which postcode and house number pairs exist
+ *
+ * The house-number blocks of a postcode are decompressed once and reused for
+ * consecutive queries with the same postcode, so sorting the input by
+ * postcode gives the best throughput.
+ */
+export declare function containsBatch(queries: Array<[string, number]>): Array<boolean>
+
+/** One problem found by `verify_data` */
+export interface DataDiagnostic {
+  /** Key of the affected entry */
+  key: string
+  /** Shard holding the key's house-number block */
+  shard: number
+  /** Byte offset of the block within its shard */
+  offset: number
+  /** What is wrong, e.g. `declared 200 numbers but the stream ended after 40` */
+  reason: string
+}
+
+/**
+ * Get a SHA-256 fingerprint (hex) of the loaded FST, house data and kinds
+ *
+ * Identical datasets give identical fingerprints, whether they were loaded
+ * from the embedded data or from a file. Computed once and cached.
+ */
+export declare function dataFingerprint(): string
+
+/** Differences between the loaded dataset and another data file */
+export interface DatasetDiff {
+  /** Keys only in the other file */
+  added: number
+  /** Keys only in the loaded dataset */
+  removed: number
+  /** Keys in both whose house numbers differ */
+  changed: number
+  /** Keys in both with the same house numbers */
+  unchanged: number
+  /** The first added keys, in key order, up to the limit */
+  addedKeys: Array<string>
+  /** The first removed keys, in key order, up to the limit */
+  removedKeys: Array<string>
+  /** The first changed keys, in key order, up to the limit */
+  changedKeys: Array<string>
+}
+
+/** Where the loaded data came from */
+export interface DataSource {
+  /** Whether the data embedded in the binary is in use */
+  embedded: boolean
+  /** Path of the file the data was loaded from, if any */
+  path?: string
+}
+
+/** Report whether the embedded data or a file was loaded */
+export declare function dataSource(): DataSource
+
+/** Size statistics of the loaded dataset */
+export interface DataStats {
+  /** Size of the brotli-compressed blob in bytes */
+  compressedBytes: number
+  /** Size of the decompressed payload (header, FST and house data) in bytes */
+  decompressedBytes: number
+  /** `decompressed_bytes / compressed_bytes` */
+  compressionRatio: number
+}
+
+/** Report of `verify_data` */
+export interface DataVerification {
+  /** Number of keys checked */
+  keysChecked: number
+  /** Problems found, in key order */
+  diagnostics: Array<DataDiagnostic>
+  /** Whether more problems were found than the limit allowed */
+  truncated: boolean
+}
+
+/**
+ * Compare the loaded dataset with a data file, e.g. to push only deltas
+ *
+ * Both FSTs are streamed once in lockstep. House numbers are compared after
+ * decoding, so a block re-encoded with another encoding is not a change.
+ * Each key list holds at most `limit` keys (the default result limit when
+ * omitted); the counts are always complete. The file is only read for the
+ * comparison and does not replace the loaded data.
+ */
+export declare function diffWithFile(path: string, codec?: Codec | undefined | null, limit?: number | undefined | null): DatasetDiff
+
+/**
+ * Write the loaded dataset to a brotli-compressed data file
+ *
+ * The file has the layout `init_from_file` reads: the original single-segment
+ * layout for unsharded data, the versioned layout otherwise.
+ */
+export declare function exportToFile(path: string): void
+
+/** All `fields` bits, the default of the batch APIs */
+export const FIELD_ALL: number
+
+/** `fields` bit selecting `LookupResult.huisnummer` in the batch APIs */
+export const FIELD_HUISNUMMER: number
+
+/** `fields` bit selecting `LookupResult.postcode` in the batch APIs */
+export const FIELD_POSTCODE: number
+
+/** `fields` bit selecting `LookupResult.straat` in the batch APIs */
+export const FIELD_STRAAT: number
+
+/** `fields` bit selecting `LookupResult.woonplaats` in the batch APIs */
+export const FIELD_WOONPLAATS: number
+
+/**
+ * Structure of the loaded FST, for comparing differently built data files
+ *
+ * `key_count` and `byte_size` are reported by `fst` itself; it keeps no
+ * node or transition counts, so those come from walking the automaton.
+ */
+export interface FstStats {
+  /** Number of keys (`postcode|straat|woonplaats` entries) */
+  keyCount: number
+  /** Size of the FST in bytes, including its header and footer */
+  byteSize: number
+  /** Number of distinct nodes reachable from the root */
+  nodeCount: number
+  /** Number of transitions over all distinct nodes */
+  transitionCount: number
+  /** Number of distinct nodes that end a key */
+  finalNodeCount: number
+}
+
+/**
+ * Get node and transition counts of the loaded FST
+ *
+ * The first call walks every node once (shared suffixes are counted once);
+ * the result is cached.
+ */
+export declare function fstStats(): FstStats
+
 /** Get information about the loaded data */
 export declare function getInfo(): string
 
-/** Initialize the postcode data (called automatically on module load) */
-export declare function init(): void
+/** Get size statistics of the loaded data as numbers */
+export declare function getStats(): DataStats
+
+/**
+ * Tally postcodes by how many house numbers they have
+ *
+ * `buckets` are ascending inclusive upper bounds, e.g. `[5, 20, 100]` counts
+ * postcodes with 0–5, 6–20, 21–100 and more than 100 numbers, so the result
+ * has one more entry than `buckets`. Only block headers are read.
+ */
+export declare function houseCountHistogram(buckets: Array<number>): Array<number>
 
-/** Lookup a postcode and house number */
-export declare function lookup(postcode: string, huisnummer: number): LookupResult | null
+/**
+ * Initialize the postcode data now instead of on the first lookup
+ *
+ * Loading the module does not load the data; without `init` the first call
+ * that needs it loads the default data.
+ * If a file named `override_file_name` (default `postcode_data.override.br`)
+ * exists next to the native module, it is loaded instead of the embedded data.
+ * Builds with the `no-embedded-data` feature fail with `NotInitialized` when
+ * there is no such file and neither `init_from_file` nor `init_from_buffer`
+ * was called first.
+ */
+export declare function init(overrideFileName?: string | undefined | null): void
+
+/**
+ * Initialize the postcode data from a compressed buffer
+ *
+ * Without `codec`, the compression is detected from the buffer's first bytes.
+ */
+export declare function initFromBuffer(buffer: Buffer, codec?: Codec | undefined | null): void
+
+/**
+ * Initialize the postcode data from a compressed data file
+ *
+ * Without `codec`, the compression is detected from the file's first bytes.
+ */
+export declare function initFromFile(path: string, codec?: Codec | undefined | null): void
+
+/**
+ * Check whether a postcode is in the dataset, without touching house data
+ *
+ * Answers from a set of the distinct postcodes, so there are no false
+ * positives. The set is built on the first call and kept for the life of
+ * the process.
+ */
+export declare function isKnownPostcode(postcode: string): boolean
+
+/**
+ * Check that a string has the form of a Dutch postcode, without any data access
+ *
+ * The grammar is `[1-9][0-9]{3} ?[A-Za-z]{2}`: four digits not starting with
+ * 0, at most one space, then two letters of either case. Leading or trailing
+ * whitespace is rejected. A valid format says nothing about whether the
+ * postcode exists.
+ */
+export declare function isValidFormat(postcode: string): boolean
+
+/**
+ * Get the kind of a postcode
+ *
+ * `None` for an unknown postcode, and `Unknown` for datasets without kinds.
+ */
+export declare function kindForPostcode(postcode: string): PostcodeKind | null
+
+/** Get the timings captured while the data was loaded */
+export declare function lastLoadTimings(): LoadTimings
+
+/** Time spent in each phase of loading the data, in milliseconds */
+export interface LoadTimings {
+  /** Brotli decompression of the compressed data */
+  decompressMs: number
+  /** Construction of the FST map */
+  fstBuildMs: number
+  /** The whole load, including slicing the payload */
+  totalMs: number
+}
+
+/**
+ * Lookup a postcode and house number
+ *
+ * A `huisnummer` of 0 means "any number": the street and city of the first
+ * address in the postcode are returned with `huisnummer: 0`. Pass
+ * `zero_means_any: false` for datasets where 0 is a real house number.
+ */
+export declare function lookup(postcode: string, huisnummer: number, zeroMeansAny?: boolean | undefined | null): LookupResult | null
+
+/**
+ * Lookup every street in a postcode that has the given house number
+ *
+ * Returns at most `limit` results, or the default result limit when omitted.
+ */
+export declare function lookupAll(postcode: string, huisnummer: number, limit?: number | undefined | null): LookupAllResult
+
+/**
+ * Lazy form of `lookup_all`, yielding one match per `next()`
+ *
+ * The iterator borrows the global postcode data: the FST stream is advanced
+ * and house-number blocks are decompressed only as results are requested.
+ *
+ * This type implements JavaScript's iterable iterator protocol.
+ * On runtimes with `Iterator` helpers, its prototype also inherits those helpers.
+ *
+ * @see https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Iterator#iterator_helper_methods
+ * @see https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Iteration_protocols#the_iterator_and_iterable_protocols
+ */
+export declare class LookupAllIter extends Iterator<LookupResult, void, void> {
+}
 
-/** Lookup multiple postcodes at once (batch operation) */
-export declare function lookupBatch(queries: Array<[string, number]>): Array<LookupResult | undefined | null>
+/** Iterate over every street in a postcode that has the given house number */
+export declare function lookupAllIter(postcode: string, huisnummer: number): LookupAllIter
+
+/** Results of `lookup_all`, flagged when the result limit cut them short */
+export interface LookupAllResult {
+  items: Array<LookupResult>
+  truncated: boolean
+  /** Number of keys read from the dataset to produce the results */
+  totalExamined: number
+}
+
+/**
+ * Lookup multiple postcodes at once (batch operation)
+ *
+ * Each result carries the canonical `postcode` and the verbatim
+ * `input_postcode` it was queried with. An empty batch returns without
+ * loading the data.
+ *
+ * `fields` is a mask of `FIELD_*` bits selecting which of `postcode`,
+ * `straat`, `huisnummer` and `woonplaats` to fill in (all by default); the
+ * others are left empty or 0, which saves building and marshalling them.
+ */
+export declare function lookupBatch(queries: Array<[string, number]>, fields?: number | undefined | null): Array<LookupResult | undefined | null>
+
+/**
+ * Run `lookup_batch` on a JSON array of `{postcode, huisnummer}` objects
+ *
+ * Returns a JSON array with a result object (with the same camelCase fields
+ * as `LookupResult`) or `null` per query, in input order.
+ */
+export declare function lookupBatchJson(input: string): string
+
+/**
+ * Lookup many postcodes encoded as a packed binary buffer
+ *
+ * Input: a sequence of 10-byte records, each a 6-byte ASCII postcode
+ * (e.g. `1012AB`) followed by the house number as a little-endian `u32`.
+ *
+ * Output: one record per input record, in the same order. Each output record
+ * starts with a status byte: `0` when nothing was found, `1` when found. A
+ * found record continues with the street and the city, each encoded as a
+ * little-endian `u16` byte length followed by that many UTF-8 bytes.
+ *
+ * Large buffers are split across the available cores, in chunks of at least
+ * `BINARY_MIN_CHUNK_RECORDS` records.
+ */
+export declare function lookupBinary(queries: Buffer): Buffer
+
+/**
+ * Lookup multiple postcodes at once, grouping the results by postcode
+ *
+ * Groups follow the order in which postcodes first appear in the input. Each
+ * postcode's house-number blocks are decompressed only once. `fields`
+ * selects the result fields to fill in, as for `lookup_batch`.
+ */
+export declare function lookupGrouped(queries: Array<[string, number]>, fields?: number | undefined | null): Array<PostcodeGroup>
+
+/**
+ * Lookup a postcode and house number, restricted to one city
+ *
+ * Only a match whose `woonplaats` equals `expected_city` (case-insensitively,
+ * after resolving city aliases such as "Den Haag") is returned, which makes
+ * postcodes on a city boundary resolve deterministically. Without
+ * `expected_city` this behaves like `lookup`.
+ */
+export declare function lookupInCity(postcode: string, huisnummer: number, expectedCity?: string | undefined | null): LookupResult | null
+
+/** Outcome of `try_lookup`, explaining misses as well as hits */
+export interface LookupOutcome {
+  status: LookupStatus
+  /** The address, when `status` is `Found` */
+  result?: LookupResult
+  /**
+   * Street whose numbers lie closest to the requested one, on a miss
+   * within a known postcode
+   */
+  nearestStreet?: StreetCity
+  /** Lowest house number in the postcode, on a miss within a known postcode */
+  minHuisnummer?: number
+  /** Highest house number in the postcode, on a miss within a known postcode */
+  maxHuisnummer?: number
+}
 
 /** Result structure for postcode lookups */
 export interface LookupResult {
@@ -18,4 +414,266 @@ export interface LookupResult {
   straat: string
   huisnummer: number
   woonplaats: string
+  /** Postcode exactly as passed in, set by the batch APIs */
+  inputPostcode?: string
+  /** Kind of the postcode, for datasets that carry kinds */
+  kind?: PostcodeKind
+  /** Canonical postcode that was asked for, set by `relax_to_area` */
+  requestedPostcode?: string
+  /** House number that was asked for, set by `relax_to_area` */
+  requestedHuisnummer?: number
+}
+
+/** Why a `try_lookup` did or did not find an address */
+export declare enum LookupStatus {
+  /** The address exists */
+  Found = 'Found',
+  /** The postcode is not in the dataset */
+  UnknownPostcode = 'UnknownPostcode',
+  /** The postcode exists but the number is below or above all its numbers */
+  OutOfRange = 'OutOfRange',
+  /** The number lies within the postcode's range but is not assigned */
+  NotFound = 'NotFound'
+}
+
+/**
+ * Find the existing postcode lexicographically nearest to the given one
+ *
+ * Looks at the closest key at-or-after and the closest key before the query
+ * and returns the postcode of the one sharing the longer prefix with it
+ * (the following key on a tie). Returns `None` only for an empty dataset.
+ */
+export declare function nearestKey(postcode: string): string | null
+
+/** House numbers surrounding a requested number within a postcode */
+export interface NeighborResult {
+  /**
+   * Highest existing number below the requested one, or the number itself
+   * when it exists
+   */
+  lower?: number
+  /**
+   * Lowest existing number above the requested one, or the number itself
+   * when it exists
+   */
+  higher?: number
+  /** Whether the requested number itself exists */
+  exact: boolean
+}
+
+/**
+ * Find the nearest existing house numbers below and above `huisnummer`
+ *
+ * Numbers of every street in the postcode are considered. Both sides are
+ * `None` for an unknown postcode.
+ */
+export declare function neighbors(postcode: string, huisnummer: number): NeighborResult
+
+/** Every address of a postcode, as returned by `addresses_in_postcode` */
+export interface PostcodeAddresses {
+  postcode: string
+  /** Street of the postcode's first street in key order */
+  straat: string
+  /** City of the postcode's first street in key order */
+  woonplaats: string
+  /**
+   * Every house number of the postcode, ascending; a number on several
+   * streets appears once per street
+   */
+  huisnummers: Array<number>
+  /** All streets in key order, only when the postcode has more than one */
+  streets?: Array<StreetCity>
+  /**
+   * Index into `streets` for each entry of `huisnummers`, only when the
+   * postcode has more than one street
+   */
+  streetOfNumber?: Array<number>
+}
+
+/**
+ * Get the smallest and largest postcode in the dataset, in key order
+ *
+ * `None` for an empty dataset. Computed once and cached.
+ */
+export declare function postcodeBounds(): [string, string] | null
+
+/** Results of `lookup_grouped` for one distinct postcode */
+export interface PostcodeGroup {
+  postcode: string
+  /** Resolved addresses, in input order */
+  results: Array<LookupResult>
+  /** House numbers that were queried but not found */
+  misses: Array<number>
 }
+
+/** Type of a postcode, for datasets that carry a per-postcode kind */
+export declare enum PostcodeKind {
+  /** Regular street addresses */
+  Residential = 'Residential',
+  /** Large-volume business receivers */
+  Business = 'Business',
+  /** Postbus numbers */
+  POBox = 'POBox',
+  /** Both residential and business addresses */
+  Mixed = 'Mixed',
+  /** The dataset carries no kind for this postcode */
+  Unknown = 'Unknown'
+}
+
+/**
+ * List the postcodes containing a street, sorted and without duplicates
+ *
+ * Street and city match case-insensitively and city aliases are resolved;
+ * `woonplaats` narrows a street name that occurs in several cities. The
+ * street index behind this is built on the first call and kept for the life
+ * of the process.
+ */
+export declare function postcodesForStreet(straat: string, woonplaats?: string | undefined | null): Array<string>
+
+/**
+ * Lookup an address, falling back to another postcode in the same 4-digit area
+ *
+ * On an exact miss, the postcodes of the area are ranked by how far their
+ * letters are from the requested ones (`AA`..`ZZ` counted as 0..675), the
+ * first in key order winning a tie. The closest postcode that has
+ * `huisnummer` is returned; when none has it, the closest postcode is
+ * returned with `huisnummer: 0`. `None` for a malformed postcode or an empty
+ * area.
+ *
+ * Every result carries `requested_postcode` and `requested_huisnummer`, so it
+ * records both what was asked for and what matched.
+ */
+export declare function relaxToArea(postcode: string, huisnummer: number): LookupResult | null
+
+/**
+ * Pick one stable, typical address for a postcode, e.g. for a map pin
+ *
+ * Uses the street with the most house numbers (the first in key order on a
+ * tie) and the median of its numbers (the lower one for an even count).
+ */
+export declare function representativeAddress(postcode: string): LookupResult | null
+
+/**
+ * Draw a reproducible, uniform random sample of `n` addresses
+ *
+ * Every key is a candidate, resolved to the first house number of its block.
+ * The same `seed` yields the same sample on every run and machine for the
+ * same dataset. Results are in key order; fewer than `n` are returned when
+ * the dataset has fewer keys.
+ *
+ * A `number` seed must be an integer within `Number.MAX_SAFE_INTEGER`; pass a
+ * `bigint` for larger seeds. Any `bigint` from -2^63 to 2^64 - 1 is accepted,
+ * a negative seed meaning its two's complement.
+ */
+export declare function sample(n: number, seed: number | bigint): Array<LookupResult>
+
+/**
+ * Incremental postcode typeahead that narrows one keystroke at a time
+ *
+ * The session keeps the FST node reached by the current input, so
+ * `push_char` follows a single transition instead of re-walking the FST from
+ * the root. It borrows the global postcode data and holds one node address
+ * and one input character per keystroke; nothing else stays allocated between
+ * calls.
+ */
+export declare class SearchSession {
+  constructor()
+  /** Append one character, returning whether any postcode still matches */
+  pushChar(c: string): boolean
+  /**
+   * Remove the last character, returning whether any postcode matches
+   *
+   * Does nothing on an empty input.
+   */
+  popChar(): boolean
+  /** Current input without whitespace, uppercased */
+  prefix(): string
+  /** List up to `limit` distinct postcodes starting with the input, in order */
+  results(limit: number): Array<string>
+}
+
+/**
+ * Set how `lookup` resolves a number shared by several streets of a postcode
+ *
+ * Defaults to `FirstMatch`. Also applies to `lookup_batch` and
+ * `lookup_binary`; `lookup_all` always returns every match.
+ */
+export declare function setAmbiguityPolicy(policy: AmbiguityPolicy): void
+
+/** Set the cap used by enumeration APIs when no explicit `limit` is passed */
+export declare function setDefaultResultLimit(limit: number): void
+
+/**
+ * Set the casing of `woonplaats` (and optionally `straat`) in lookup results
+ *
+ * Defaults to `AsStored`, which returns names exactly as in the dataset.
+ */
+export declare function setOutputCase(cityCase: CityCase, applyToStraat?: boolean | undefined | null): void
+
+/**
+ * Make lookups fail with a `DataCorrupted` error on malformed data
+ *
+ * Off by default, in which case keys that are not valid UTF-8 or do not have
+ * `postcode|straat|woonplaats` form are silently skipped. Strict mode also
+ * fully checks each house-number block a lookup reads, failing with the
+ * block's offset and what is wrong with it.
+ */
+export declare function setStrictMode(strict: boolean): void
+
+/** A street together with the city it lies in */
+export interface StreetCity {
+  straat: string
+  woonplaats: string
+}
+
+/**
+ * List the distinct street/city pairs under a postcode prefix, in key order
+ *
+ * Returns at most `limit` pairs, or the default result limit when omitted.
+ */
+export declare function streetCityPairs(prefix: string, limit?: number | undefined | null): StreetCityPairsResult
+
+/** Results of `street_city_pairs`, flagged when the result limit cut them short */
+export interface StreetCityPairsResult {
+  items: Array<StreetCity>
+  truncated: boolean
+  /** Number of keys read from the dataset to produce the results */
+  totalExamined: number
+}
+
+/**
+ * Suggest existing postcodes close to a (possibly unknown) postcode
+ *
+ * Candidates within a small edit distance are ranked by edit distance
+ * ascending, then by postcode in byte order. Each postcode appears at most
+ * once, so this is a total order: the output for a given input and dataset
+ * is the same on every call and platform.
+ */
+export declare function suggestPostcodes(postcode: string, limit: number): Array<string>
+
+/**
+ * Lookup a postcode and house number, reporting why a lookup missed
+ *
+ * Unlike `lookup`, a miss distinguishes an unknown postcode from a number
+ * outside the postcode's range or in a gap between its numbers.
+ */
+export declare function tryLookup(postcode: string, huisnummer: number): LookupOutcome
+
+/**
+ * Check a full address against the dataset in a single call
+ *
+ * Street and city are compared case-insensitively, after resolving city
+ * aliases (see `add_city_alias`). The individual flags can
+ * each be true while `valid` is false, e.g. when the number exists but on
+ * another street of the postcode.
+ */
+export declare function validateAddress(postcode: string, huisnummer: number, expectedStraat: string, expectedWoonplaats: string): AddressValidation
+
+/**
+ * Check every key and house-number block of the loaded data
+ *
+ * Where strict mode fails on the first problem a lookup runs into, this
+ * walks the whole dataset and collects up to `limit` diagnostics (the
+ * default result limit when omitted). No diagnostics means the data is sound.
+ */
+export declare function verifyData(limit?: number | undefined | null): DataVerification
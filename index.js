@@ -393,7 +393,59 @@ if (!nativeBinding) {
 }
 
 module.exports = nativeBinding
+module.exports.addCityAlias = nativeBinding.addCityAlias
+module.exports.addressesInPostcode = nativeBinding.addressesInPostcode
+module.exports.AmbiguityPolicy = nativeBinding.AmbiguityPolicy
+module.exports.citiesForPostcode = nativeBinding.citiesForPostcode
+module.exports.citiesForPostcodes = nativeBinding.citiesForPostcodes
+module.exports.CityCase = nativeBinding.CityCase
+module.exports.Codec = nativeBinding.Codec
+module.exports.containsBatch = nativeBinding.containsBatch
+module.exports.dataFingerprint = nativeBinding.dataFingerprint
+module.exports.dataSource = nativeBinding.dataSource
+module.exports.diffWithFile = nativeBinding.diffWithFile
+module.exports.exportToFile = nativeBinding.exportToFile
+module.exports.FIELD_ALL = nativeBinding.FIELD_ALL
+module.exports.FIELD_HUISNUMMER = nativeBinding.FIELD_HUISNUMMER
+module.exports.FIELD_POSTCODE = nativeBinding.FIELD_POSTCODE
+module.exports.FIELD_STRAAT = nativeBinding.FIELD_STRAAT
+module.exports.FIELD_WOONPLAATS = nativeBinding.FIELD_WOONPLAATS
+module.exports.fstStats = nativeBinding.fstStats
 module.exports.getInfo = nativeBinding.getInfo
+module.exports.getStats = nativeBinding.getStats
+module.exports.houseCountHistogram = nativeBinding.houseCountHistogram
 module.exports.init = nativeBinding.init
+module.exports.initFromBuffer = nativeBinding.initFromBuffer
+module.exports.initFromFile = nativeBinding.initFromFile
+module.exports.isKnownPostcode = nativeBinding.isKnownPostcode
+module.exports.isValidFormat = nativeBinding.isValidFormat
+module.exports.kindForPostcode = nativeBinding.kindForPostcode
+module.exports.lastLoadTimings = nativeBinding.lastLoadTimings
 module.exports.lookup = nativeBinding.lookup
+module.exports.lookupAll = nativeBinding.lookupAll
+module.exports.LookupAllIter = nativeBinding.LookupAllIter
+module.exports.lookupAllIter = nativeBinding.lookupAllIter
 module.exports.lookupBatch = nativeBinding.lookupBatch
+module.exports.lookupBatchJson = nativeBinding.lookupBatchJson
+module.exports.lookupBinary = nativeBinding.lookupBinary
+module.exports.lookupGrouped = nativeBinding.lookupGrouped
+module.exports.lookupInCity = nativeBinding.lookupInCity
+module.exports.LookupStatus = nativeBinding.LookupStatus
+module.exports.nearestKey = nativeBinding.nearestKey
+module.exports.neighbors = nativeBinding.neighbors
+module.exports.postcodeBounds = nativeBinding.postcodeBounds
+module.exports.PostcodeKind = nativeBinding.PostcodeKind
+module.exports.postcodesForStreet = nativeBinding.postcodesForStreet
+module.exports.relaxToArea = nativeBinding.relaxToArea
+module.exports.representativeAddress = nativeBinding.representativeAddress
+module.exports.sample = nativeBinding.sample
+module.exports.SearchSession = nativeBinding.SearchSession
+module.exports.setAmbiguityPolicy = nativeBinding.setAmbiguityPolicy
+module.exports.setDefaultResultLimit = nativeBinding.setDefaultResultLimit
+module.exports.setOutputCase = nativeBinding.setOutputCase
+module.exports.setStrictMode = nativeBinding.setStrictMode
+module.exports.streetCityPairs = nativeBinding.streetCityPairs
+module.exports.suggestPostcodes = nativeBinding.suggestPostcodes
+module.exports.tryLookup = nativeBinding.tryLookup
+module.exports.validateAddress = nativeBinding.validateAddress
+module.exports.verifyData = nativeBinding.verifyData
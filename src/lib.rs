@@ -1,167 +1,3457 @@
-use napi::Result;
+use napi::bindgen_prelude::{BigInt, Buffer, Either};
+use napi::iterator::ScopedGenerator;
+use napi::{Env, Error, Result, Status};
 
+use brotli::enc::BrotliEncoderParams;
 use brotli::Decompressor;
 use fst::{
-    automaton::{Automaton, Str},
-    IntoStreamer, Map, Streamer,
+    automaton::{Automaton, Levenshtein, Str},
+    IntoStreamer, Map, Set, Streamer,
 };
 use napi_derive::napi;
-use std::io::Read;
-use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+use std::sync::{LazyLock, OnceLock, PoisonError, RwLock};
+use std::time::Instant;
 
 // Embed the compressed data directly in the binary
+#[cfg(not(feature = "no-embedded-data"))]
 static COMPRESSED_DATA: &[u8] = include_bytes!("../postcode_data.br");
 
+// File that, when present next to the native module, replaces the embedded data
+const DEFAULT_OVERRIDE_FILE_NAME: &str = "postcode_data.override.br";
+
 // Global state for the loaded data
+//
+// Synchronization: the dataset is written once through this `OnceLock` and is
+// immutable afterwards, so lookups on any thread (including the scoped worker
+// threads of `lookup_binary`) share it without locking. Derived values cached
+// on it (`fingerprint`, `postcode_bounds`, `fst_stats`, `known_postcodes`,
+// `street_index`) are `OnceLock`s as well: `get_or_init` runs the initializer
+// once while concurrent first callers block until its value is stored. The one
+// duplicated work is in `load_default_data`, which parses an override file
+// before entering the lock, so racing first loads may each parse it; only the
+// first parsed dataset is stored. The settings below are independent atomics
+// read with `Relaxed` ordering; each is a self-contained flag or index that
+// guards no other memory, so a lookup racing a setter sees either the old or
+// the new value. `set_output_case` stores two of them, so a lookup racing it
+// may briefly combine the new casing with the old street flag. The city alias
+// table is the one setting behind a lock: an `RwLock` that city filters read
+// and `add_city_alias` writes.
 static POSTCODE_DATA: OnceLock<PostcodeData> = OnceLock::new();
 
+// Whether malformed keys raise `DataCorrupted` instead of being skipped
+static STRICT_MODE: AtomicBool = AtomicBool::new(false);
+
+// `CityCase` applied to result names, stored as its index
+static OUTPUT_CASE: AtomicU8 = AtomicU8::new(0);
+
+// Whether `OUTPUT_CASE` also applies to street names
+static CASE_APPLIES_TO_STRAAT: AtomicBool = AtomicBool::new(false);
+
+// `AmbiguityPolicy` applied by `lookup`, stored as its index
+static AMBIGUITY_POLICY: AtomicU8 = AtomicU8::new(0);
+
+// Cap applied by enumeration APIs when the caller passes no explicit limit
+static DEFAULT_RESULT_LIMIT: AtomicU32 = AtomicU32::new(10_000);
+
+// Lowercased city synonyms mapped to the lowercased name stored in the data
+static CITY_ALIASES: LazyLock<RwLock<HashMap<String, String>>> = LazyLock::new(|| {
+    RwLock::new(
+        BUILTIN_CITY_ALIASES
+            .iter()
+            .map(|&(from, to)| (from.to_string(), to.to_string()))
+            .collect(),
+    )
+});
+
+// Aliases every process starts with, lowercased
+const BUILTIN_CITY_ALIASES: &[(&str, &str)] = &[
+    ("den haag", "'s-gravenhage"),
+    ("the hague", "'s-gravenhage"),
+    ("s-gravenhage", "'s-gravenhage"),
+    ("den bosch", "'s-hertogenbosch"),
+    ("s-hertogenbosch", "'s-hertogenbosch"),
+];
+
+// Brotli quality and log2 window size used by `export_to_file`
+const EXPORT_BROTLI_QUALITY: i32 = 11;
+const EXPORT_BROTLI_WINDOW: i32 = 22;
+
+/// `fields` bit selecting `LookupResult.postcode` in the batch APIs
+#[napi]
+pub const FIELD_POSTCODE: u32 = 1;
+/// `fields` bit selecting `LookupResult.straat` in the batch APIs
+#[napi]
+pub const FIELD_STRAAT: u32 = 2;
+/// `fields` bit selecting `LookupResult.huisnummer` in the batch APIs
+#[napi]
+pub const FIELD_HUISNUMMER: u32 = 4;
+/// `fields` bit selecting `LookupResult.woonplaats` in the batch APIs
+#[napi]
+pub const FIELD_WOONPLAATS: u32 = 8;
+/// All `fields` bits, the default of the batch APIs
+#[napi]
+pub const FIELD_ALL: u32 = FIELD_POSTCODE | FIELD_STRAAT | FIELD_HUISNUMMER | FIELD_WOONPLAATS;
+
+// Magic bytes opening a versioned payload; legacy payloads start with the FST length
+const DATA_MAGIC: &[u8; 4] = b"PRST";
+
+// Payload version with `house_data` split into shards
+const DATA_VERSION_SHARDED: u32 = 2;
+
+// Payload version adding a postcode kinds section after the shards
+const DATA_VERSION_KINDS: u32 = 3;
+
+// Size of one kinds record: a 6-byte postcode and a `PostcodeKind` byte
+const KIND_RECORD_LEN: usize = 7;
+
+// FST values keep the shard id above this bit and the offset within the shard below it
+const SHARD_SHIFT: u32 = 56;
+
 #[derive(Debug)]
 struct PostcodeData {
     fst_map: Map<Vec<u8>>,
-    house_data: Vec<u8>,
+    /// House-number blocks, one segment per shard (a single one for legacy data)
+    house_data: Vec<Vec<u8>>,
+    /// Kinds records sorted by postcode, for datasets that carry them
+    kinds: Option<Vec<u8>>,
+    compressed_len: usize,
+    decompressed_len: usize,
+    origin: DataOrigin,
+    load_timings: LoadTimings,
+    fingerprint: OnceLock<String>,
+    postcode_bounds: OnceLock<Option<(String, String)>>,
+    fst_stats: OnceLock<FstStats>,
+    /// Distinct postcodes, built on the first `is_known_postcode` call
+    known_postcodes: OnceLock<Set<Vec<u8>>>,
+    street_index: OnceLock<StreetIndex>,
+}
+
+/// Postcodes per street, built on the first `postcodes_for_street` call
+#[derive(Debug)]
+struct StreetIndex {
+    /// Lowercased city names, referenced by index
+    cities: Vec<String>,
+    /// Lowercased street name to its `(postcode, city index)` pairs, in key
+    /// order; postcodes are stored inline as their six bytes
+    streets: HashMap<String, Vec<([u8; 6], u32)>>,
+}
+
+impl StreetIndex {
+    fn build(data: &PostcodeData) -> Self {
+        let mut city_ids: HashMap<String, u32> = HashMap::new();
+        let mut cities = Vec::new();
+        let mut streets: HashMap<String, Vec<([u8; 6], u32)>> = HashMap::new();
+        let mut stream = data.fst_map.stream();
+        while let Some((key_bytes, _)) = stream.next() {
+            let Some((postcode, straat, woonplaats)) = split_key(key_bytes) else {
+                continue;
+            };
+            let Ok(postcode) = <[u8; 6]>::try_from(postcode.as_bytes()) else {
+                continue;
+            };
+            let city = *city_ids
+                .entry(woonplaats.to_lowercase())
+                .or_insert_with_key(|city| {
+                    cities.push(city.clone());
+                    cities.len() as u32 - 1
+                });
+            streets
+                .entry(straat.to_lowercase())
+                .or_default()
+                .push((postcode, city));
+        }
+        StreetIndex { cities, streets }
+    }
+}
+
+impl PostcodeData {
+    /// House-number block an FST value points at, or an empty block when the
+    /// shard or offset is out of bounds
+    fn block(&self, value: u64) -> &[u8] {
+        let shard = (value >> SHARD_SHIFT) as usize;
+        let offset = (value & ((1 << SHARD_SHIFT) - 1)) as usize;
+        self.house_data
+            .get(shard)
+            .and_then(|segment| segment.get(offset..))
+            .unwrap_or(&[])
+    }
+
+    /// Like `block`, but in strict mode a block that does not decode cleanly
+    /// is a `DataCorrupted` error giving its location and the reason
+    fn strict_block(&self, value: u64, strict: bool) -> Result<&[u8]> {
+        if strict {
+            self.check_block(value).map_err(|reason| {
+                data_corrupted(format!(
+                    "house block at {}: {}",
+                    self.block_location(value),
+                    reason
+                ))
+            })?;
+        }
+        Ok(self.block(value))
+    }
+
+    /// Fully decode the block an FST value points at, explaining why it is
+    /// corrupt when the lenient decoder would silently stop early
+    fn check_block(&self, value: u64) -> std::result::Result<Vec<u32>, String> {
+        let shard = (value >> SHARD_SHIFT) as usize;
+        let offset = (value & ((1 << SHARD_SHIFT) - 1)) as usize;
+        let Some(segment) = self.house_data.get(shard) else {
+            return Err(format!(
+                "shard {} does not exist, the data has {}",
+                shard,
+                self.house_data.len()
+            ));
+        };
+        let Some(block) = segment.get(offset..) else {
+            return Err(format!(
+                "offset lies past the end of the house data ({} bytes)",
+                segment.len()
+            ));
+        };
+        let header = match block {
+            [lo, hi, ..] => u16::from_le_bytes([*lo, *hi]),
+            _ => {
+                return Err(format!(
+                    "block header needs 2 bytes but the house data ends after {}",
+                    block.len()
+                ))
+            }
+        };
+        if header & BLOCK_TAGGED != 0 {
+            match block.get(2) {
+                None => return Err("encoding tag missing after a tagged header".to_string()),
+                Some(&BLOCK_ENCODING_DELTA | &BLOCK_ENCODING_RUNS) => {}
+                Some(tag) => return Err(format!("unknown block encoding tag {}", tag)),
+            }
+        }
+        let declared = block_count(block);
+        let numbers = decompress_house_numbers(block);
+        if numbers.len() < declared {
+            return Err(format!(
+                "declared {} numbers but the stream ended after {}",
+                declared,
+                numbers.len()
+            ));
+        }
+        if let Some(i) = numbers.windows(2).position(|pair| pair[0] >= pair[1]) {
+            return Err(format!(
+                "number {} at index {} does not exceed the previous number {}",
+                numbers[i + 1],
+                i + 1,
+                numbers[i]
+            ));
+        }
+        Ok(numbers)
+    }
+
+    /// Human-readable location of a block, naming the shard only for sharded data
+    fn block_location(&self, value: u64) -> String {
+        let offset = value & ((1 << SHARD_SHIFT) - 1);
+        if self.house_data.len() == 1 {
+            format!("offset {}", offset)
+        } else {
+            format!("shard {} offset {}", value >> SHARD_SHIFT, offset)
+        }
+    }
+
+    /// Kind of an uppercased postcode, or `None` if the dataset has no kinds
+    ///
+    /// Postcodes without a record are residential.
+    fn kind_of(&self, postcode_upper: &str) -> Option<PostcodeKind> {
+        let kinds = self.kinds.as_ref()?;
+        let record = |i: usize| &kinds[i * KIND_RECORD_LEN..(i + 1) * KIND_RECORD_LEN];
+        let (mut low, mut high) = (0, kinds.len() / KIND_RECORD_LEN);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match record(mid)[..6].cmp(postcode_upper.as_bytes()) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Some(PostcodeKind::from_byte(record(mid)[6])),
+            }
+        }
+        Some(PostcodeKind::Residential)
+    }
+
+    fn house_data_len(&self) -> usize {
+        self.house_data.iter().map(Vec::len).sum()
+    }
+}
+
+#[derive(Debug)]
+enum DataOrigin {
+    #[cfg_attr(feature = "no-embedded-data", allow(dead_code))]
+    Embedded,
+    File(PathBuf),
+    Buffer,
 }
 
 /// Result structure for postcode lookups
 #[napi(object)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LookupResult {
     pub postcode: String,
     pub straat: String,
     pub huisnummer: u32,
     pub woonplaats: String,
+    /// Postcode exactly as passed in, set by the batch APIs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_postcode: Option<String>,
+    /// Kind of the postcode, for datasets that carry kinds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<PostcodeKind>,
+    /// Canonical postcode that was asked for, set by `relax_to_area`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_postcode: Option<String>,
+    /// House number that was asked for, set by `relax_to_area`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_huisnummer: Option<u32>,
+}
+
+impl LookupResult {
+    /// Build a result, applying the configured output casing to the names
+    ///
+    /// `data` is the dataset the address was found in, which supplies the kind.
+    fn new(
+        data: &PostcodeData,
+        postcode: String,
+        straat: &str,
+        huisnummer: u32,
+        woonplaats: &str,
+    ) -> Self {
+        Self::new_selected(data, postcode, straat, huisnummer, woonplaats, FIELD_ALL)
+    }
+
+    /// Build a result with only the `fields` bits filled in, leaving the
+    /// other fields empty (or 0) so they cost nothing to build and marshal
+    fn new_selected(
+        data: &PostcodeData,
+        postcode: String,
+        straat: &str,
+        huisnummer: u32,
+        woonplaats: &str,
+        fields: u32,
+    ) -> Self {
+        let kind = data.kind_of(&postcode);
+        let case = CityCase::current();
+        let straat = if fields & FIELD_STRAAT == 0 {
+            String::new()
+        } else if CASE_APPLIES_TO_STRAAT.load(Ordering::Relaxed) {
+            case.apply(straat)
+        } else {
+            straat.to_string()
+        };
+        LookupResult {
+            postcode: if fields & FIELD_POSTCODE == 0 {
+                String::new()
+            } else {
+                postcode
+            },
+            straat,
+            huisnummer: if fields & FIELD_HUISNUMMER == 0 {
+                0
+            } else {
+                huisnummer
+            },
+            woonplaats: if fields & FIELD_WOONPLAATS == 0 {
+                String::new()
+            } else {
+                case.apply(woonplaats)
+            },
+            input_postcode: None,
+            kind,
+            requested_postcode: None,
+            requested_huisnummer: None,
+        }
+    }
+
+    fn with_input(mut self, input_postcode: &str) -> Self {
+        self.input_postcode = Some(input_postcode.to_string());
+        self
+    }
+
+    fn with_requested(mut self, postcode: &str, huisnummer: u32) -> Self {
+        self.requested_postcode = Some(canonical_postcode(postcode));
+        self.requested_huisnummer = Some(huisnummer);
+        self
+    }
+}
+
+/// Type of a postcode, for datasets that carry a per-postcode kind
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PostcodeKind {
+    /// Regular street addresses
+    Residential,
+    /// Large-volume business receivers
+    Business,
+    /// Postbus numbers
+    POBox,
+    /// Both residential and business addresses
+    Mixed,
+    /// The dataset carries no kind for this postcode
+    Unknown,
+}
+
+impl PostcodeKind {
+    fn from_byte(byte: u8) -> Self {
+        match byte {
+            0 => PostcodeKind::Residential,
+            1 => PostcodeKind::Business,
+            2 => PostcodeKind::POBox,
+            3 => PostcodeKind::Mixed,
+            _ => PostcodeKind::Unknown,
+        }
+    }
+}
+
+/// Casing applied to city (and optionally street) names in lookup results
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CityCase {
+    /// Names exactly as stored in the dataset
+    AsStored,
+    /// Dutch-aware title case, e.g. "Bergen op Zoom" and "'s-Gravenhage"
+    TitleCase,
+    /// All capitals
+    UpperCase,
+}
+
+impl CityCase {
+    fn current() -> Self {
+        match OUTPUT_CASE.load(Ordering::Relaxed) {
+            1 => CityCase::TitleCase,
+            2 => CityCase::UpperCase,
+            _ => CityCase::AsStored,
+        }
+    }
+
+    fn apply(self, name: &str) -> String {
+        match self {
+            CityCase::AsStored => name.to_string(),
+            CityCase::TitleCase => dutch_title_case(name),
+            CityCase::UpperCase => name.to_uppercase(),
+        }
+    }
+}
+
+/// What `lookup` does when several streets of a postcode have the number
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmbiguityPolicy {
+    /// Return the first matching street in key order
+    FirstMatch,
+    /// Fail with an `AmbiguousAddress` error
+    Error,
+    /// Return the matching street with the most house numbers
+    MostNumbers,
+}
+
+impl AmbiguityPolicy {
+    fn current() -> Self {
+        match AMBIGUITY_POLICY.load(Ordering::Relaxed) {
+            1 => AmbiguityPolicy::Error,
+            2 => AmbiguityPolicy::MostNumbers,
+            _ => AmbiguityPolicy::FirstMatch,
+        }
+    }
+}
+
+/// A street together with the city it lies in
+#[napi(object)]
+pub struct StreetCity {
+    pub straat: String,
+    pub woonplaats: String,
+}
+
+/// Time spent in each phase of loading the data, in milliseconds
+#[napi(object)]
+#[derive(Debug, Clone, Copy)]
+pub struct LoadTimings {
+    /// Brotli decompression of the compressed data
+    pub decompress_ms: f64,
+    /// Construction of the FST map
+    pub fst_build_ms: f64,
+    /// The whole load, including slicing the payload
+    pub total_ms: f64,
+}
+
+/// Where the loaded data came from
+#[napi(object)]
+pub struct DataSource {
+    /// Whether the data embedded in the binary is in use
+    pub embedded: bool,
+    /// Path of the file the data was loaded from, if any
+    pub path: Option<String>,
 }
 
-/// Initialize the postcode data (called automatically on module load)
+/// Initialize the postcode data now instead of on the first lookup
+///
+/// Loading the module does not load the data; without `init` the first call
+/// that needs it loads the default data.
+/// If a file named `override_file_name` (default `postcode_data.override.br`)
+/// exists next to the native module, it is loaded instead of the embedded data.
+/// Builds with the `no-embedded-data` feature fail with `NotInitialized` when
+/// there is no such file and neither `init_from_file` nor `init_from_buffer`
+/// was called first.
 #[napi]
-pub fn init() -> Result<()> {
-    let _ = POSTCODE_DATA.get_or_init(|| load_data());
+pub fn init(override_file_name: Option<String>) -> Result<()> {
+    if POSTCODE_DATA.get().is_none() {
+        let file_name = override_file_name
+            .as_deref()
+            .unwrap_or(DEFAULT_OVERRIDE_FILE_NAME);
+        load_default_data(file_name)?;
+    }
     Ok(())
 }
 
+/// Compression of a data file or buffer
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    /// Brotli, the format of the embedded data
+    Brotli,
+    /// Gzip, available in builds with the `gzip` feature
+    Gzip,
+}
+
+impl Codec {
+    /// Gzip when the data starts with the gzip magic bytes, else brotli
+    /// (brotli streams have no magic bytes of their own)
+    fn detect(compressed: &[u8]) -> Self {
+        if compressed.starts_with(&[0x1f, 0x8b]) {
+            Codec::Gzip
+        } else {
+            Codec::Brotli
+        }
+    }
+}
+
+/// Initialize the postcode data from a compressed data file
+///
+/// Without `codec`, the compression is detected from the file's first bytes.
+#[napi]
+pub fn init_from_file(path: String, codec: Option<Codec>) -> Result<()> {
+    let compressed = read_data_file(Path::new(&path))?;
+    let data = parse_data(&compressed, codec, DataOrigin::File(PathBuf::from(path)))?;
+    install_data(data)
+}
+
+/// Differences between the loaded dataset and another data file
+#[napi(object)]
+pub struct DatasetDiff {
+    /// Keys only in the other file
+    pub added: u32,
+    /// Keys only in the loaded dataset
+    pub removed: u32,
+    /// Keys in both whose house numbers differ
+    pub changed: u32,
+    /// Keys in both with the same house numbers
+    pub unchanged: u32,
+    /// The first added keys, in key order, up to the limit
+    pub added_keys: Vec<String>,
+    /// The first removed keys, in key order, up to the limit
+    pub removed_keys: Vec<String>,
+    /// The first changed keys, in key order, up to the limit
+    pub changed_keys: Vec<String>,
+}
+
+/// Compare the loaded dataset with a data file, e.g. to push only deltas
+///
+/// Both FSTs are streamed once in lockstep. House numbers are compared after
+/// decoding, so a block re-encoded with another encoding is not a change.
+/// Each key list holds at most `limit` keys (the default result limit when
+/// omitted); the counts are always complete. The file is only read for the
+/// comparison and does not replace the loaded data.
+#[napi]
+pub fn diff_with_file(
+    path: String,
+    codec: Option<Codec>,
+    limit: Option<u32>,
+) -> Result<DatasetDiff> {
+    let data = loaded_data()?;
+    let compressed = read_data_file(Path::new(&path))?;
+    let other = parse_data(&compressed, codec, DataOrigin::File(PathBuf::from(path)))?;
+    let limit = limit.unwrap_or_else(|| DEFAULT_RESULT_LIMIT.load(Ordering::Relaxed)) as usize;
+    Ok(diff_fst(data, &other, limit))
+}
+
+/// Initialize the postcode data from a compressed buffer
+///
+/// Without `codec`, the compression is detected from the buffer's first bytes.
+#[napi]
+pub fn init_from_buffer(buffer: Buffer, codec: Option<Codec>) -> Result<()> {
+    let data = parse_data(&buffer, codec, DataOrigin::Buffer)?;
+    install_data(data)
+}
+
+/// Write the loaded dataset to a brotli-compressed data file
+///
+/// The file has the layout `init_from_file` reads: the original single-segment
+/// layout for unsharded data, the versioned layout otherwise.
+#[napi]
+pub fn export_to_file(path: String) -> Result<()> {
+    let data = loaded_data()?;
+    let write_error = |e: std::io::Error| {
+        Error::new(
+            Status::GenericFailure,
+            format!("Could not write data file {}: {}", path, e),
+        )
+    };
+    let mut payload = Vec::with_capacity(data.decompressed_len);
+    write_payload(data, &mut payload).map_err(write_error)?;
+    let params = BrotliEncoderParams {
+        quality: EXPORT_BROTLI_QUALITY,
+        lgwin: EXPORT_BROTLI_WINDOW,
+        ..Default::default()
+    };
+
+    let file = std::fs::File::create(&path).map_err(write_error)?;
+    let mut writer = BufWriter::new(file);
+    brotli::BrotliCompress(&mut payload.as_slice(), &mut writer, &params).map_err(write_error)?;
+    writer
+        .into_inner()
+        .map_err(|e| write_error(e.into_error()))?
+        .sync_all()
+        .map_err(write_error)
+}
+
+/// Report whether the embedded data or a file was loaded
+#[napi]
+pub fn data_source() -> Result<DataSource> {
+    let data = loaded_data()?;
+    let path = match &data.origin {
+        DataOrigin::File(path) => Some(path.display().to_string()),
+        DataOrigin::Embedded | DataOrigin::Buffer => None,
+    };
+    Ok(DataSource {
+        embedded: matches!(data.origin, DataOrigin::Embedded),
+        path,
+    })
+}
+
 /// Get information about the loaded data
 #[napi]
 pub fn get_info() -> Result<String> {
-    let data = POSTCODE_DATA.get_or_init(|| load_data());
-    let memory_usage = (data.fst_map.as_fst().as_bytes().len() + data.house_data.len()) as f64 / 1_000_000.0;
+    let data = loaded_data()?;
+    let memory_usage = (data.fst_map.as_fst().as_bytes().len() + data.house_data_len()) as f64 / 1_000_000.0;
     Ok(format!(
         "postRUST NPM Package\nMemory usage: {:.2} MB\nCompressed data size: {:.2} MB",
         memory_usage,
-        COMPRESSED_DATA.len() as f64 / 1_000_000.0
+        data.compressed_len as f64 / 1_000_000.0
     ))
 }
 
-/// Lookup a postcode and house number
+/// Size statistics of the loaded dataset
+#[napi(object)]
+pub struct DataStats {
+    /// Size of the brotli-compressed blob in bytes
+    pub compressed_bytes: i64,
+    /// Size of the decompressed payload (header, FST and house data) in bytes
+    pub decompressed_bytes: i64,
+    /// `decompressed_bytes / compressed_bytes`
+    pub compression_ratio: f64,
+}
+
+/// Get size statistics of the loaded data as numbers
 #[napi]
-pub fn lookup(postcode: String, huisnummer: u32) -> Result<Option<LookupResult>> {
-    let data = POSTCODE_DATA.get_or_init(|| load_data());
-    Ok(lookup_address_fst(data, &postcode, huisnummer))
+pub fn get_stats() -> Result<DataStats> {
+    let data = loaded_data()?;
+    let compressed_bytes = data.compressed_len;
+    let decompressed_bytes = data.decompressed_len;
+    Ok(DataStats {
+        compressed_bytes: compressed_bytes as i64,
+        decompressed_bytes: decompressed_bytes as i64,
+        compression_ratio: decompressed_bytes as f64 / compressed_bytes as f64,
+    })
 }
 
-/// Lookup multiple postcodes at once (batch operation)
+/// Structure of the loaded FST, for comparing differently built data files
+///
+/// `key_count` and `byte_size` are reported by `fst` itself; it keeps no
+/// node or transition counts, so those come from walking the automaton.
+#[napi(object)]
+#[derive(Debug, Clone, Copy)]
+pub struct FstStats {
+    /// Number of keys (`postcode|straat|woonplaats` entries)
+    pub key_count: i64,
+    /// Size of the FST in bytes, including its header and footer
+    pub byte_size: i64,
+    /// Number of distinct nodes reachable from the root
+    pub node_count: i64,
+    /// Number of transitions over all distinct nodes
+    pub transition_count: i64,
+    /// Number of distinct nodes that end a key
+    pub final_node_count: i64,
+}
+
+/// Get node and transition counts of the loaded FST
+///
+/// The first call walks every node once (shared suffixes are counted once);
+/// the result is cached.
 #[napi]
-pub fn lookup_batch(queries: Vec<(String, u32)>) -> Result<Vec<Option<LookupResult>>> {
-    let data = POSTCODE_DATA.get_or_init(|| load_data());
-    let results = queries
-        .iter()
-        .map(|(postcode, huisnummer)| lookup_address_fst(data, postcode, *huisnummer))
-        .collect();
-    Ok(results)
+pub fn fst_stats() -> Result<FstStats> {
+    let data = loaded_data()?;
+    let stats = data.fst_stats.get_or_init(|| {
+        let fst = data.fst_map.as_fst();
+        let mut stats = FstStats {
+            key_count: fst.len() as i64,
+            byte_size: fst.size() as i64,
+            node_count: 0,
+            transition_count: 0,
+            final_node_count: 0,
+        };
+        let mut seen = HashSet::new();
+        let mut pending = vec![fst.root().addr()];
+        while let Some(addr) = pending.pop() {
+            if !seen.insert(addr) {
+                continue;
+            }
+            let node = fst.node(addr);
+            stats.node_count += 1;
+            stats.transition_count += node.len() as i64;
+            if node.is_final() {
+                stats.final_node_count += 1;
+            }
+            pending.extend(node.transitions().map(|transition| transition.addr));
+        }
+        stats
+    });
+    Ok(*stats)
 }
 
-// === Internal implementation (same as in the main server) ===
+/// One problem found by `verify_data`
+#[napi(object)]
+pub struct DataDiagnostic {
+    /// Key of the affected entry
+    pub key: String,
+    /// Shard holding the key's house-number block
+    pub shard: u32,
+    /// Byte offset of the block within its shard
+    pub offset: i64,
+    /// What is wrong, e.g. `declared 200 numbers but the stream ended after 40`
+    pub reason: String,
+}
 
-fn load_data() -> PostcodeData {
-    let mut decompressor = Decompressor::new(COMPRESSED_DATA, 4096);
-    let mut decompressed_data = Vec::new();
-    decompressor
-        .read_to_end(&mut decompressed_data)
-        .expect("Could not decompress brotli data");
+/// Report of `verify_data`
+#[napi(object)]
+pub struct DataVerification {
+    /// Number of keys checked
+    pub keys_checked: u32,
+    /// Problems found, in key order
+    pub diagnostics: Vec<DataDiagnostic>,
+    /// Whether more problems were found than the limit allowed
+    pub truncated: bool,
+}
+
+/// Check every key and house-number block of the loaded data
+///
+/// Where strict mode fails on the first problem a lookup runs into, this
+/// walks the whole dataset and collects up to `limit` diagnostics (the
+/// default result limit when omitted). No diagnostics means the data is sound.
+#[napi]
+pub fn verify_data(limit: Option<u32>) -> Result<DataVerification> {
+    let data = loaded_data()?;
+    let limit = limit.unwrap_or_else(|| DEFAULT_RESULT_LIMIT.load(Ordering::Relaxed)) as usize;
+    let mut keys_checked = 0u32;
+    let mut diagnostics = Vec::new();
+    let mut truncated = false;
+    let mut stream = data.fst_map.stream();
+    while let Some((key_bytes, value)) = stream.next() {
+        keys_checked += 1;
+        let mut reasons = Vec::new();
+        if split_key(key_bytes).is_none() {
+            reasons.push("malformed key".to_string());
+        }
+        if let Err(reason) = data.check_block(value) {
+            reasons.push(reason);
+        }
+        for reason in reasons {
+            if diagnostics.len() == limit {
+                truncated = true;
+                break;
+            }
+            diagnostics.push(DataDiagnostic {
+                key: String::from_utf8_lossy(key_bytes).into_owned(),
+                shard: (value >> SHARD_SHIFT) as u32,
+                offset: (value & ((1 << SHARD_SHIFT) - 1)) as i64,
+                reason,
+            });
+        }
+    }
+    Ok(DataVerification {
+        keys_checked,
+        diagnostics,
+        truncated,
+    })
+}
 
-    let fst_len = u64::from_le_bytes(decompressed_data[0..8].try_into().unwrap()) as usize;
-    let house_data_len = u64::from_le_bytes(decompressed_data[8..16].try_into().unwrap()) as usize;
+/// Get a SHA-256 fingerprint (hex) of the loaded FST, house data and kinds
+///
+/// Identical datasets give identical fingerprints, whether they were loaded
+/// from the embedded data or from a file. Computed once and cached.
+#[napi]
+pub fn data_fingerprint() -> Result<String> {
+    let data = loaded_data()?;
+    let fingerprint = data.fingerprint.get_or_init(|| {
+        let mut hasher = Sha256::new();
+        hasher.update(data.fst_map.as_fst().as_bytes());
+        for segment in &data.house_data {
+            hasher.update(segment);
+        }
+        if let Some(kinds) = &data.kinds {
+            hasher.update(kinds);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    });
+    Ok(fingerprint.clone())
+}
 
-    let fst_bytes = decompressed_data[16..16 + fst_len].to_vec();
-    let house_data_bytes = decompressed_data[16 + fst_len..16 + fst_len + house_data_len].to_vec();
+/// Get the smallest and largest postcode in the dataset, in key order
+///
+/// `None` for an empty dataset. Computed once and cached.
+#[napi]
+pub fn postcode_bounds() -> Result<Option<(String, String)>> {
+    let data = loaded_data()?;
+    let bounds = data.postcode_bounds.get_or_init(|| {
+        let fst = data.fst_map.as_fst();
+        let first = data.fst_map.stream().next().map(|(key, _)| key.to_vec())?;
+        let last = max_key_from(fst, fst.root(), Vec::new());
+        Some((postcode_of_key(&first), postcode_of_key(&last)))
+    });
+    Ok(bounds.clone())
+}
 
-    let fst_map = Map::new(fst_bytes).expect("FST data is corrupted or invalid");
+/// Get the timings captured while the data was loaded
+#[napi]
+pub fn last_load_timings() -> Result<LoadTimings> {
+    let data = loaded_data()?;
+    Ok(data.load_timings)
+}
 
-    PostcodeData {
-        fst_map,
-        house_data: house_data_bytes,
+/// Lookup a postcode and house number
+///
+/// A `huisnummer` of 0 means "any number": the street and city of the first
+/// address in the postcode are returned with `huisnummer: 0`. Pass
+/// `zero_means_any: false` for datasets where 0 is a real house number.
+#[napi]
+pub fn lookup(
+    postcode: String,
+    huisnummer: u32,
+    zero_means_any: Option<bool>,
+) -> Result<Option<LookupResult>> {
+    let data = loaded_data()?;
+    if huisnummer == 0 && zero_means_any.unwrap_or(true) {
+        return lookup_postcode_fst(data, &postcode);
     }
+    lookup_address_fst(data, &postcode, huisnummer, FIELD_ALL)
 }
 
-fn lookup_address_fst(
-    data: &PostcodeData,
-    postcode: &str,
-    house_number: u32,
-) -> Option<LookupResult> {
-    let postcode_upper = postcode.to_uppercase();
-    let prefix = format!("{}|", postcode_upper);
+/// Results of `lookup_all`, flagged when the result limit cut them short
+#[napi(object)]
+pub struct LookupAllResult {
+    pub items: Vec<LookupResult>,
+    pub truncated: bool,
+    /// Number of keys read from the dataset to produce the results
+    pub total_examined: u32,
+}
 
-    let automaton = Str::new(&prefix).starts_with();
-    let mut stream = data.fst_map.search(automaton).into_stream();
+/// Results of `street_city_pairs`, flagged when the result limit cut them short
+#[napi(object)]
+pub struct StreetCityPairsResult {
+    pub items: Vec<StreetCity>,
+    pub truncated: bool,
+    /// Number of keys read from the dataset to produce the results
+    pub total_examined: u32,
+}
 
-    while let Some((key_bytes, offset)) = stream.next() {
-        let key_str = std::str::from_utf8(key_bytes).unwrap_or("");
-        let house_numbers_compressed = &data.house_data[offset as usize..];
-        let house_numbers = decompress_house_numbers(house_numbers_compressed);
-
-        if house_numbers.binary_search(&house_number).is_ok() {
-            let parts: Vec<&str> = key_str.split('|').collect();
-            if parts.len() == 3 {
-                return Some(LookupResult {
-                    postcode: postcode_upper,
-                    straat: parts[1].to_string(),
-                    huisnummer: house_number,
-                    woonplaats: parts[2].to_string(),
-                });
+/// Make lookups fail with a `DataCorrupted` error on malformed data
+///
+/// Off by default, in which case keys that are not valid UTF-8 or do not have
+/// `postcode|straat|woonplaats` form are silently skipped. Strict mode also
+/// fully checks each house-number block a lookup reads, failing with the
+/// block's offset and what is wrong with it.
+#[napi]
+pub fn set_strict_mode(strict: bool) {
+    STRICT_MODE.store(strict, Ordering::Relaxed);
+}
+
+/// Set the casing of `woonplaats` (and optionally `straat`) in lookup results
+///
+/// Defaults to `AsStored`, which returns names exactly as in the dataset.
+#[napi]
+pub fn set_output_case(city_case: CityCase, apply_to_straat: Option<bool>) {
+    let index = match city_case {
+        CityCase::AsStored => 0,
+        CityCase::TitleCase => 1,
+        CityCase::UpperCase => 2,
+    };
+    OUTPUT_CASE.store(index, Ordering::Relaxed);
+    CASE_APPLIES_TO_STRAAT.store(apply_to_straat.unwrap_or(false), Ordering::Relaxed);
+}
+
+/// Set how `lookup` resolves a number shared by several streets of a postcode
+///
+/// Defaults to `FirstMatch`. Also applies to `lookup_batch` and
+/// `lookup_binary`; `lookup_all` always returns every match.
+#[napi]
+pub fn set_ambiguity_policy(policy: AmbiguityPolicy) {
+    let index = match policy {
+        AmbiguityPolicy::FirstMatch => 0,
+        AmbiguityPolicy::Error => 1,
+        AmbiguityPolicy::MostNumbers => 2,
+    };
+    AMBIGUITY_POLICY.store(index, Ordering::Relaxed);
+}
+
+/// Set the cap used by enumeration APIs when no explicit `limit` is passed
+#[napi]
+pub fn set_default_result_limit(limit: u32) {
+    DEFAULT_RESULT_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// Lookup every street in a postcode that has the given house number
+///
+/// Returns at most `limit` results, or the default result limit when omitted.
+#[napi]
+pub fn lookup_all(
+    postcode: String,
+    huisnummer: u32,
+    limit: Option<u32>,
+) -> Result<LookupAllResult> {
+    let data = loaded_data()?;
+    let limit = limit.unwrap_or_else(|| DEFAULT_RESULT_LIMIT.load(Ordering::Relaxed)) as usize;
+    let mut matches = AddressMatches::new(data, &postcode, huisnummer);
+    let mut items = matches
+        .by_ref()
+        .take(limit.saturating_add(1))
+        .collect::<Result<Vec<_>>>()?;
+    let truncated = items.len() > limit;
+    items.truncate(limit);
+    Ok(LookupAllResult {
+        items,
+        truncated,
+        total_examined: matches.examined,
+    })
+}
+
+/// Lazy form of `lookup_all`, yielding one match per `next()`
+///
+/// The iterator borrows the global postcode data: the FST stream is advanced
+/// and house-number blocks are decompressed only as results are requested.
+#[napi(iterator)]
+pub struct LookupAllIter {
+    matches: AddressMatches<'static>,
+}
+
+#[napi]
+impl<'env> ScopedGenerator<'env> for LookupAllIter {
+    type Yield = LookupResult;
+    type Next = ();
+    type Return = ();
+
+    fn next(&mut self, env: &'env Env, _value: Option<Self::Next>) -> Option<Self::Yield> {
+        match self.matches.next()? {
+            Ok(result) => Some(result),
+            Err(e) => {
+                // Strict mode: end the iteration by throwing from `next()`
+                let _ = env.throw_error(&e.reason, Some(e.status.as_ref()));
+                None
             }
         }
     }
-    None
 }
 
-fn decompress_house_numbers(mut compressed_data: &[u8]) -> Vec<u32> {
-    if compressed_data.len() < 2 {
-        return Vec::new();
+/// Iterate over every street in a postcode that has the given house number
+#[napi]
+pub fn lookup_all_iter(postcode: String, huisnummer: u32) -> Result<LookupAllIter> {
+    let data = loaded_data()?;
+    Ok(LookupAllIter {
+        matches: AddressMatches::new(data, &postcode, huisnummer),
+    })
+}
+
+/// Make `from` an alias of the city `to` in the city filters
+///
+/// Both names are case-insensitive and `to` should be the name as stored in
+/// the data. Aliases resolve one level deep; a later alias for the same name
+/// replaces the earlier one, built-in aliases included.
+#[napi]
+pub fn add_city_alias(from: String, to: String) -> Result<()> {
+    if from.trim().is_empty() || to.trim().is_empty() {
+        return Err(Error::new(
+            Status::InvalidArg,
+            "City alias names must not be empty".to_string(),
+        ));
     }
-    let len = u16::from_le_bytes(compressed_data[0..2].try_into().unwrap()) as usize;
-    if len == 0 {
-        return Vec::new();
+    CITY_ALIASES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(from.to_lowercase(), to.to_lowercase());
+    Ok(())
+}
+
+/// Lowercase a city name and resolve it through the alias table
+fn normalize_city(city: &str) -> String {
+    let city = city.to_lowercase();
+    let aliases = CITY_ALIASES.read().unwrap_or_else(PoisonError::into_inner);
+    match aliases.get(&city) {
+        Some(canonical) => canonical.clone(),
+        None => city,
     }
-    let mut nums = Vec::with_capacity(len);
-    compressed_data = &compressed_data[2..];
+}
 
-    if compressed_data.len() < 2 {
-        return nums;
+/// Lookup a postcode and house number, restricted to one city
+///
+/// Only a match whose `woonplaats` equals `expected_city` (case-insensitively,
+/// after resolving city aliases such as "Den Haag") is returned, which makes
+/// postcodes on a city boundary resolve deterministically. Without
+/// `expected_city` this behaves like `lookup`.
+#[napi]
+pub fn lookup_in_city(
+    postcode: String,
+    huisnummer: u32,
+    expected_city: Option<String>,
+) -> Result<Option<LookupResult>> {
+    let data = loaded_data()?;
+    let Some(expected_city) = expected_city else {
+        return lookup_address_fst(data, &postcode, huisnummer, FIELD_ALL);
+    };
+    let expected_city = normalize_city(&expected_city);
+    for result in AddressMatches::new(data, &postcode, huisnummer) {
+        let result = result?;
+        if result.woonplaats.to_lowercase() == expected_city {
+            return Ok(Some(result));
+        }
     }
+    Ok(None)
+}
 
-    let first_num = u16::from_le_bytes(compressed_data[0..2].try_into().unwrap()) as u32;
-    nums.push(first_num);
-    compressed_data = &compressed_data[2..];
+/// Why a `try_lookup` did or did not find an address
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupStatus {
+    /// The address exists
+    Found,
+    /// The postcode is not in the dataset
+    UnknownPostcode,
+    /// The postcode exists but the number is below or above all its numbers
+    OutOfRange,
+    /// The number lies within the postcode's range but is not assigned
+    NotFound,
+}
 
-    let mut last_num = first_num;
-    while nums.len() < len {
-        if compressed_data.is_empty() {
-            break;
-        }
-        let delta_marker = compressed_data[0];
-        compressed_data = &compressed_data[1..];
+/// Verdict of `validate_address`, one flag per check
+#[napi(object)]
+pub struct AddressValidation {
+    /// The postcode has at least one address
+    pub postcode_exists: bool,
+    /// Some street of the postcode has the house number
+    pub house_number_exists: bool,
+    /// The postcode has a street named `expected_straat`
+    pub street_matches: bool,
+    /// The postcode lies (partly) in `expected_woonplaats`
+    pub city_matches: bool,
+    /// A single address has the house number, street and city together
+    pub valid: bool,
+}
 
-        let delta = if delta_marker == 0 {
-            if compressed_data.len() < 2 {
-                break;
+/// Check a full address against the dataset in a single call
+///
+/// Street and city are compared case-insensitively, after resolving city
+/// aliases (see `add_city_alias`). The individual flags can
+/// each be true while `valid` is false, e.g. when the number exists but on
+/// another street of the postcode.
+#[napi]
+pub fn validate_address(
+    postcode: String,
+    huisnummer: u32,
+    expected_straat: String,
+    expected_woonplaats: String,
+) -> Result<AddressValidation> {
+    let data = loaded_data()?;
+    validate_address_fst(
+        data,
+        &postcode,
+        huisnummer,
+        &expected_straat.to_lowercase(),
+        &normalize_city(&expected_woonplaats),
+    )
+}
+
+/// Outcome of `try_lookup`, explaining misses as well as hits
+#[napi(object)]
+pub struct LookupOutcome {
+    pub status: LookupStatus,
+    /// The address, when `status` is `Found`
+    pub result: Option<LookupResult>,
+    /// Street whose numbers lie closest to the requested one, on a miss
+    /// within a known postcode
+    pub nearest_street: Option<StreetCity>,
+    /// Lowest house number in the postcode, on a miss within a known postcode
+    pub min_huisnummer: Option<u32>,
+    /// Highest house number in the postcode, on a miss within a known postcode
+    pub max_huisnummer: Option<u32>,
+}
+
+/// Lookup a postcode and house number, reporting why a lookup missed
+///
+/// Unlike `lookup`, a miss distinguishes an unknown postcode from a number
+/// outside the postcode's range or in a gap between its numbers.
+#[napi]
+pub fn try_lookup(postcode: String, huisnummer: u32) -> Result<LookupOutcome> {
+    let data = loaded_data()?;
+    try_lookup_fst(data, &postcode, huisnummer)
+}
+
+/// Results of `lookup_grouped` for one distinct postcode
+#[napi(object)]
+pub struct PostcodeGroup {
+    pub postcode: String,
+    /// Resolved addresses, in input order
+    pub results: Vec<LookupResult>,
+    /// House numbers that were queried but not found
+    pub misses: Vec<u32>,
+}
+
+/// Lookup multiple postcodes at once, grouping the results by postcode
+///
+/// Groups follow the order in which postcodes first appear in the input. Each
+/// postcode's house-number blocks are decompressed only once. `fields`
+/// selects the result fields to fill in, as for `lookup_batch`.
+#[napi]
+pub fn lookup_grouped(
+    queries: Vec<(String, u32)>,
+    fields: Option<u32>,
+) -> Result<Vec<PostcodeGroup>> {
+    let fields = selected_fields(fields)?;
+    if queries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let data = loaded_data()?;
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    let mut grouped: Vec<(String, Vec<(&str, u32)>)> = Vec::new();
+    for (postcode, huisnummer) in &queries {
+        let canonical = canonical_postcode(postcode);
+        let index = *group_index.entry(canonical.clone()).or_insert_with(|| {
+            grouped.push((canonical, Vec::new()));
+            grouped.len() - 1
+        });
+        grouped[index].1.push((postcode, *huisnummer));
+    }
+
+    let groups = grouped
+        .into_iter()
+        .map(|(postcode, house_numbers)| {
+            let blocks = postcode_blocks(data, &postcode)?;
+            let mut results = Vec::new();
+            let mut misses = Vec::new();
+            for (input_postcode, house_number) in house_numbers {
+                let found = blocks
+                    .iter()
+                    .find(|block| block.house_numbers.binary_search(&house_number).is_ok());
+                match found {
+                    Some(block) => results.push(
+                        LookupResult::new_selected(
+                            data,
+                            postcode.clone(),
+                            &block.straat,
+                            house_number,
+                            &block.woonplaats,
+                            fields,
+                        )
+                        .with_input(input_postcode),
+                    ),
+                    None => misses.push(house_number),
+                }
             }
-            let d = u16::from_le_bytes(compressed_data[0..2].try_into().unwrap());
-            compressed_data = &compressed_data[2..];
-            d as u32
+            Ok(PostcodeGroup {
+                postcode,
+                results,
+                misses,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(groups)
+}
+
+/// Pick one stable, typical address for a postcode, e.g. for a map pin
+///
+/// Uses the street with the most house numbers (the first in key order on a
+/// tie) and the median of its numbers (the lower one for an even count).
+#[napi]
+pub fn representative_address(postcode: String) -> Result<Option<LookupResult>> {
+    let data = loaded_data()?;
+    let postcode_upper = canonical_postcode(&postcode);
+    let blocks = postcode_blocks(data, &postcode_upper)?;
+    let result = blocks
+        .iter()
+        .rev()
+        .filter(|block| !block.house_numbers.is_empty())
+        .max_by_key(|block| block.house_numbers.len())
+        .map(|block| {
+            let median = block.house_numbers[(block.house_numbers.len() - 1) / 2];
+            LookupResult::new(
+                data,
+                postcode_upper,
+                &block.straat,
+                median,
+                &block.woonplaats,
+            )
+        });
+    Ok(result)
+}
+
+/// Every address of a postcode, as returned by `addresses_in_postcode`
+#[napi(object)]
+pub struct PostcodeAddresses {
+    pub postcode: String,
+    /// Street of the postcode's first street in key order
+    pub straat: String,
+    /// City of the postcode's first street in key order
+    pub woonplaats: String,
+    /// Every house number of the postcode, ascending; a number on several
+    /// streets appears once per street
+    pub huisnummers: Vec<u32>,
+    /// All streets in key order, only when the postcode has more than one
+    pub streets: Option<Vec<StreetCity>>,
+    /// Index into `streets` for each entry of `huisnummers`, only when the
+    /// postcode has more than one street
+    pub street_of_number: Option<Vec<u32>>,
+}
+
+/// Expand a postcode into all of its house numbers in one call
+///
+/// Names follow the configured output casing, like lookup results. Returns
+/// `None` for an unknown postcode.
+#[napi]
+pub fn addresses_in_postcode(postcode: String) -> Result<Option<PostcodeAddresses>> {
+    let data = loaded_data()?;
+    let postcode_upper = canonical_postcode(&postcode);
+    let blocks = postcode_blocks(data, &postcode_upper)?;
+    let Some(first) = blocks.first() else {
+        return Ok(None);
+    };
+    let case = CityCase::current();
+    let case_straat = CASE_APPLIES_TO_STRAAT.load(Ordering::Relaxed);
+    let cased = |block: &StreetBlock| StreetCity {
+        straat: if case_straat {
+            case.apply(&block.straat)
         } else {
-            delta_marker as u32
-        };
+            block.straat.clone()
+        },
+        woonplaats: case.apply(&block.woonplaats),
+    };
+    let StreetCity { straat, woonplaats } = cased(first);
+
+    if blocks.len() == 1 {
+        return Ok(Some(PostcodeAddresses {
+            postcode: postcode_upper,
+            straat,
+            woonplaats,
+            huisnummers: first.house_numbers.clone(),
+            streets: None,
+            street_of_number: None,
+        }));
+    }
+    // Stable sort keeps numbers shared by several streets in street order
+    let mut numbered: Vec<(u32, u32)> = blocks
+        .iter()
+        .enumerate()
+        .flat_map(|(street, block)| {
+            block
+                .house_numbers
+                .iter()
+                .map(move |&number| (number, street as u32))
+        })
+        .collect();
+    numbered.sort_by_key(|&(number, _)| number);
+    let (huisnummers, street_of_number) = numbered.into_iter().unzip();
+    Ok(Some(PostcodeAddresses {
+        postcode: postcode_upper,
+        straat,
+        woonplaats,
+        huisnummers,
+        streets: Some(blocks.iter().map(cased).collect()),
+        street_of_number: Some(street_of_number),
+    }))
+}
+
+/// Largest integer a JS `number` holds exactly, 2^53 - 1
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// Draw a reproducible, uniform random sample of `n` addresses
+///
+/// Every key is a candidate, resolved to the first house number of its block.
+/// The same `seed` yields the same sample on every run and machine for the
+/// same dataset. Results are in key order; fewer than `n` are returned when
+/// the dataset has fewer keys.
+///
+/// A `number` seed must be an integer within `Number.MAX_SAFE_INTEGER`; pass a
+/// `bigint` for larger seeds. Any `bigint` from -2^63 to 2^64 - 1 is accepted,
+/// a negative seed meaning its two's complement.
+#[napi]
+pub fn sample(n: u32, seed: Either<f64, BigInt>) -> Result<Vec<LookupResult>> {
+    let seed = match seed {
+        Either::A(seed) if seed.fract() == 0.0 && seed.abs() <= MAX_SAFE_INTEGER => {
+            seed as i64 as u64
+        }
+        Either::A(_) => {
+            return Err(Error::new(
+                Status::InvalidArg,
+                "Seed must be an integer within Number.MAX_SAFE_INTEGER, pass a bigint for larger seeds"
+                    .to_string(),
+            ))
+        }
+        Either::B(seed) => match seed.get_i128() {
+            (seed, true) if (i64::MIN as i128..=u64::MAX as i128).contains(&seed) => seed as u64,
+            _ => {
+                return Err(Error::new(
+                    Status::InvalidArg,
+                    "Seed must be between -2^63 and 2^64 - 1".to_string(),
+                ))
+            }
+        },
+    };
+    let data = loaded_data()?;
+    sample_fst(data, n as usize, seed)
+}
+
+/// House numbers surrounding a requested number within a postcode
+#[napi(object)]
+pub struct NeighborResult {
+    /// Highest existing number below the requested one, or the number itself
+    /// when it exists
+    pub lower: Option<u32>,
+    /// Lowest existing number above the requested one, or the number itself
+    /// when it exists
+    pub higher: Option<u32>,
+    /// Whether the requested number itself exists
+    pub exact: bool,
+}
+
+/// Find the nearest existing house numbers below and above `huisnummer`
+///
+/// Numbers of every street in the postcode are considered. Both sides are
+/// `None` for an unknown postcode.
+#[napi]
+pub fn neighbors(postcode: String, huisnummer: u32) -> Result<NeighborResult> {
+    let data = loaded_data()?;
+    let mut result = NeighborResult {
+        lower: None,
+        higher: None,
+        exact: false,
+    };
+    for block in postcode_blocks(data, &canonical_postcode(&postcode))? {
+        let numbers = &block.house_numbers;
+        let below = numbers.partition_point(|&n| n < huisnummer);
+        if numbers.get(below) == Some(&huisnummer) {
+            return Ok(NeighborResult {
+                lower: Some(huisnummer),
+                higher: Some(huisnummer),
+                exact: true,
+            });
+        }
+        if let Some(&lower) = below.checked_sub(1).and_then(|i| numbers.get(i)) {
+            result.lower = result.lower.max(Some(lower));
+        }
+        if let Some(&higher) = numbers.get(below) {
+            result.higher = Some(result.higher.map_or(higher, |h| h.min(higher)));
+        }
+    }
+    Ok(result)
+}
+
+/// Lookup multiple postcodes at once (batch operation)
+///
+/// Each result carries the canonical `postcode` and the verbatim
+/// `input_postcode` it was queried with. An empty batch returns without
+/// loading the data.
+///
+/// `fields` is a mask of `FIELD_*` bits selecting which of `postcode`,
+/// `straat`, `huisnummer` and `woonplaats` to fill in (all by default); the
+/// others are left empty or 0, which saves building and marshalling them.
+#[napi]
+pub fn lookup_batch(
+    queries: Vec<(String, u32)>,
+    fields: Option<u32>,
+) -> Result<Vec<Option<LookupResult>>> {
+    let fields = selected_fields(fields)?;
+    if queries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let data = loaded_data()?;
+    queries
+        .iter()
+        .map(|(postcode, huisnummer)| {
+            let result = lookup_address_fst(data, postcode, *huisnummer, fields)?;
+            Ok(result.map(|result| result.with_input(postcode)))
+        })
+        .collect()
+}
+
+/// Validate a `fields` mask of the batch APIs, defaulting to all fields
+fn selected_fields(fields: Option<u32>) -> Result<u32> {
+    match fields {
+        None => Ok(FIELD_ALL),
+        Some(fields) if fields & !FIELD_ALL == 0 => Ok(fields),
+        Some(fields) => Err(Error::new(
+            Status::InvalidArg,
+            format!(
+                "Invalid fields mask {}: only FIELD_* bits (at most {}) are allowed",
+                fields, FIELD_ALL
+            ),
+        )),
+    }
+}
+
+/// One query of `lookup_batch_json`
+#[derive(Deserialize)]
+struct JsonQuery {
+    postcode: String,
+    huisnummer: u32,
+}
+
+/// Run `lookup_batch` on a JSON array of `{postcode, huisnummer}` objects
+///
+/// Returns a JSON array with a result object (with the same camelCase fields
+/// as `LookupResult`) or `null` per query, in input order.
+#[napi]
+pub fn lookup_batch_json(input: String) -> Result<String> {
+    let queries: Vec<JsonQuery> = serde_json::from_str(&input).map_err(|e| {
+        Error::new(
+            Status::InvalidArg,
+            format!("Invalid lookupBatchJson input: {}", e),
+        )
+    })?;
+    let queries = queries
+        .into_iter()
+        .map(|query| (query.postcode, query.huisnummer))
+        .collect();
+    let results = lookup_batch(queries, None)?;
+    serde_json::to_string(&results).map_err(|e| {
+        Error::new(
+            Status::GenericFailure,
+            format!("Could not serialize results: {}", e),
+        )
+    })
+}
+
+/// List the distinct street/city pairs under a postcode prefix, in key order
+///
+/// Returns at most `limit` pairs, or the default result limit when omitted.
+#[napi]
+pub fn street_city_pairs(prefix: String, limit: Option<u32>) -> Result<StreetCityPairsResult> {
+    let data = loaded_data()?;
+    let limit = limit.unwrap_or_else(|| DEFAULT_RESULT_LIMIT.load(Ordering::Relaxed));
+    Ok(street_city_pairs_fst(data, &prefix, limit as usize))
+}
+
+/// Check which postcode and house number pairs exist
+///
+/// The house-number blocks of a postcode are decompressed once and reused for
+/// consecutive queries with the same postcode, so sorting the input by
+/// postcode gives the best throughput.
+#[napi]
+pub fn contains_batch(queries: Vec<(String, u32)>) -> Result<Vec<bool>> {
+    if queries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let data = loaded_data()?;
+    let mut cached: Option<(String, Vec<StreetBlock>)> = None;
+    queries
+        .iter()
+        .map(|(postcode, huisnummer)| {
+            let postcode_upper = canonical_postcode(postcode);
+            let blocks = match &mut cached {
+                Some((cached_postcode, blocks)) if *cached_postcode == postcode_upper => blocks,
+                _ => {
+                    let blocks = postcode_blocks(data, &postcode_upper)?;
+                    &mut cached.insert((postcode_upper, blocks)).1
+                }
+            };
+            Ok(blocks
+                .iter()
+                .any(|block| block.house_numbers.binary_search(huisnummer).is_ok()))
+        })
+        .collect()
+}
+
+/// List every distinct city a postcode belongs to, sorted
+///
+/// Unknown postcodes give an empty list.
+#[napi]
+pub fn cities_for_postcode(postcode: String) -> Result<Vec<String>> {
+    let data = loaded_data()?;
+    let prefix = format!("{}|", canonical_postcode(&postcode));
+    let automaton = Str::new(&prefix).starts_with();
+    let mut stream = data.fst_map.search(automaton).into_stream();
+
+    let strict = STRICT_MODE.load(Ordering::Relaxed);
+    let mut cities = Vec::new();
+    while let Some((key_bytes, offset)) = stream.next() {
+        let (_, _, woonplaats) = match split_key(key_bytes) {
+            Some(parts) => parts,
+            None if strict => return Err(malformed_key_error(key_bytes)),
+            None => continue,
+        };
+        data.strict_block(offset, strict)?;
+        cities.push(woonplaats.to_string());
+    }
+    cities.sort();
+    cities.dedup();
+    Ok(cities)
+}
+
+/// Get the kind of a postcode
+///
+/// `None` for an unknown postcode, and `Unknown` for datasets without kinds.
+#[napi]
+pub fn kind_for_postcode(postcode: String) -> Result<Option<PostcodeKind>> {
+    let data = loaded_data()?;
+    let postcode_upper = canonical_postcode(&postcode);
+    let exists = data
+        .fst_map
+        .range()
+        .ge(format!("{}|", postcode_upper))
+        .lt(format!("{}}}", postcode_upper))
+        .into_stream()
+        .next()
+        .is_some();
+    if !exists {
+        return Ok(None);
+    }
+    Ok(Some(
+        data.kind_of(&postcode_upper)
+            .unwrap_or(PostcodeKind::Unknown),
+    ))
+}
+
+/// List the postcodes containing a street, sorted and without duplicates
+///
+/// Street and city match case-insensitively and city aliases are resolved;
+/// `woonplaats` narrows a street name that occurs in several cities. The
+/// street index behind this is built on the first call and kept for the life
+/// of the process.
+#[napi]
+pub fn postcodes_for_street(straat: String, woonplaats: Option<String>) -> Result<Vec<String>> {
+    let data = loaded_data()?;
+    let index = data.street_index.get_or_init(|| StreetIndex::build(data));
+    let Some(entries) = index.streets.get(&straat.to_lowercase()) else {
+        return Ok(Vec::new());
+    };
+    let woonplaats = woonplaats.map(|woonplaats| normalize_city(&woonplaats));
+    let mut postcodes: Vec<String> = entries
+        .iter()
+        .filter(|(_, city)| {
+            woonplaats
+                .as_ref()
+                .is_none_or(|woonplaats| index.cities[*city as usize] == *woonplaats)
+        })
+        .map(|(postcode, _)| String::from_utf8_lossy(postcode).into_owned())
+        .collect();
+    postcodes.sort_unstable();
+    postcodes.dedup();
+    Ok(postcodes)
+}
+
+/// Check whether a postcode is in the dataset, without touching house data
+///
+/// Answers from a set of the distinct postcodes, so there are no false
+/// positives. The set is built on the first call and kept for the life of
+/// the process.
+#[napi]
+pub fn is_known_postcode(postcode: String) -> Result<bool> {
+    let data = loaded_data()?;
+    let known = data.known_postcodes.get_or_init(|| {
+        let mut postcodes: Vec<Vec<u8>> = Vec::new();
+        let mut stream = data.fst_map.stream();
+        while let Some((key_bytes, _)) = stream.next() {
+            let postcode = key_bytes.split(|&b| b == b'|').next().unwrap_or(key_bytes);
+            if postcodes.last().is_none_or(|last| last != postcode) {
+                postcodes.push(postcode.to_vec());
+            }
+        }
+        // Keys are sorted as whole strings, which need not sort their prefixes
+        postcodes.sort_unstable();
+        postcodes.dedup();
+        Set::from_iter(postcodes).expect("postcodes are sorted and deduplicated")
+    });
+    Ok(known.contains(canonical_postcode(&postcode)))
+}
+
+/// Resolve the city of each postcode, in input order
+///
+/// Each city is the `woonplaats` of the postcode's first street, as returned
+/// by `lookup` with house number 0, or `None` for an unknown postcode.
+#[napi]
+pub fn cities_for_postcodes(postcodes: Vec<String>) -> Result<Vec<Option<String>>> {
+    if postcodes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let data = loaded_data()?;
+    postcodes
+        .iter()
+        .map(|postcode| Ok(lookup_postcode_fst(data, postcode)?.map(|result| result.woonplaats)))
+        .collect()
+}
+
+/// Tally postcodes by how many house numbers they have
+///
+/// `buckets` are ascending inclusive upper bounds, e.g. `[5, 20, 100]` counts
+/// postcodes with 0–5, 6–20, 21–100 and more than 100 numbers, so the result
+/// has one more entry than `buckets`. Only block headers are read.
+#[napi]
+pub fn house_count_histogram(buckets: Vec<u32>) -> Result<Vec<u32>> {
+    if buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(Error::new(
+            Status::InvalidArg,
+            "Histogram buckets must be strictly ascending".to_string(),
+        ));
+    }
+    let data = loaded_data()?;
+    let mut histogram = vec![0u32; buckets.len() + 1];
+    let mut tally = |count: u32| histogram[buckets.partition_point(|&bound| bound < count)] += 1;
+
+    let mut stream = data.fst_map.stream();
+    let mut current: Option<(Vec<u8>, u32)> = None;
+    while let Some((key_bytes, offset)) = stream.next() {
+        let postcode = key_bytes.split(|&b| b == b'|').next().unwrap_or_default();
+        let count = block_count(data.block(offset)) as u32;
+        match &mut current {
+            Some((current_postcode, total)) if current_postcode.as_slice() == postcode => {
+                *total += count;
+            }
+            _ => {
+                if let Some((_, total)) = current.replace((postcode.to_vec(), count)) {
+                    tally(total);
+                }
+            }
+        }
+    }
+    if let Some((_, total)) = current {
+        tally(total);
+    }
+    Ok(histogram)
+}
+
+/// Check that a string has the form of a Dutch postcode, without any data access
+///
+/// The grammar is `[1-9][0-9]{3} ?[A-Za-z]{2}`: four digits not starting with
+/// 0, at most one space, then two letters of either case. Leading or trailing
+/// whitespace is rejected. A valid format says nothing about whether the
+/// postcode exists.
+#[napi]
+pub fn is_valid_format(postcode: String) -> bool {
+    let bytes = postcode.as_bytes();
+    let (digits, letters) = match bytes.len() {
+        6 => bytes.split_at(4),
+        7 if bytes[4] == b' ' => (&bytes[..4], &bytes[5..]),
+        _ => return false,
+    };
+    matches!(digits[0], b'1'..=b'9')
+        && digits.iter().all(u8::is_ascii_digit)
+        && letters.iter().all(u8::is_ascii_alphabetic)
+}
+
+/// Lookup an address, falling back to another postcode in the same 4-digit area
+///
+/// On an exact miss, the postcodes of the area are ranked by how far their
+/// letters are from the requested ones (`AA`..`ZZ` counted as 0..675), the
+/// first in key order winning a tie. The closest postcode that has
+/// `huisnummer` is returned; when none has it, the closest postcode is
+/// returned with `huisnummer: 0`. `None` for a malformed postcode or an empty
+/// area.
+///
+/// Every result carries `requested_postcode` and `requested_huisnummer`, so it
+/// records both what was asked for and what matched.
+#[napi]
+pub fn relax_to_area(postcode: String, huisnummer: u32) -> Result<Option<LookupResult>> {
+    let data = loaded_data()?;
+    let result = match lookup_address_fst(data, &postcode, huisnummer, FIELD_ALL)? {
+        Some(result) => Some(result),
+        None => relax_to_area_fst(data, &postcode, huisnummer),
+    };
+    Ok(result.map(|result| result.with_requested(&postcode, huisnummer)))
+}
+
+/// Maximum edit distance considered by `suggest_postcodes`
+const MAX_SUGGEST_DISTANCE: u32 = 2;
+
+/// Suggest existing postcodes close to a (possibly unknown) postcode
+///
+/// Candidates within a small edit distance are ranked by edit distance
+/// ascending, then by postcode in byte order. Each postcode appears at most
+/// once, so this is a total order: the output for a given input and dataset
+/// is the same on every call and platform.
+#[napi]
+pub fn suggest_postcodes(postcode: String, limit: u32) -> Result<Vec<String>> {
+    let data = loaded_data()?;
+    Ok(suggest_postcodes_fst(data, &postcode, limit as usize))
+}
+
+/// Find the existing postcode lexicographically nearest to the given one
+///
+/// Looks at the closest key at-or-after and the closest key before the query
+/// and returns the postcode of the one sharing the longer prefix with it
+/// (the following key on a tie). Returns `None` only for an empty dataset.
+#[napi]
+pub fn nearest_key(postcode: String) -> Result<Option<String>> {
+    let data = loaded_data()?;
+    Ok(nearest_key_fst(data, &postcode))
+}
+
+/// Incremental postcode typeahead that narrows one keystroke at a time
+///
+/// The session keeps the FST node reached by the current input, so
+/// `push_char` follows a single transition instead of re-walking the FST from
+/// the root. It borrows the global postcode data and holds one node address
+/// and one input character per keystroke; nothing else stays allocated between
+/// calls.
+#[napi]
+pub struct SearchSession {
+    data: &'static PostcodeData,
+    steps: Vec<SearchStep>,
+    /// Uppercased input, one character per step that was not skipped
+    prefix: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SearchStep {
+    /// Whitespace, which postcodes may contain when typed but keys do not
+    Skipped,
+    /// The input so far leads to this FST node
+    Matched(fst::raw::CompiledAddr),
+    /// No postcode starts with the input so far
+    NoMatch,
+}
+
+#[napi]
+impl SearchSession {
+    #[napi(constructor)]
+    pub fn new() -> Result<Self> {
+        Ok(SearchSession {
+            data: loaded_data()?,
+            steps: Vec::new(),
+            prefix: String::new(),
+        })
+    }
+
+    /// Append one character, returning whether any postcode still matches
+    #[napi]
+    pub fn push_char(&mut self, c: String) -> Result<bool> {
+        let mut chars = c.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return Err(Error::new(
+                Status::InvalidArg,
+                format!("Expected a single character, got {:?}", c),
+            ));
+        };
+        if c.is_whitespace() {
+            self.steps.push(SearchStep::Skipped);
+            return Ok(self.current_node().is_some());
+        }
+
+        let c = c.to_ascii_uppercase();
+        // Only ASCII can occur in a postcode; '|' would step into the street
+        let step = match self.current_node() {
+            Some(addr) if c.is_ascii() && c != '|' => {
+                let node = self.data.fst_map.as_fst().node(addr);
+                match node.find_input(c as u8) {
+                    Some(i) => SearchStep::Matched(node.transition(i).addr),
+                    None => SearchStep::NoMatch,
+                }
+            }
+            _ => SearchStep::NoMatch,
+        };
+        self.prefix.push(c);
+        self.steps.push(step);
+        Ok(matches!(step, SearchStep::Matched(_)))
+    }
+
+    /// Remove the last character, returning whether any postcode matches
+    ///
+    /// Does nothing on an empty input.
+    #[napi]
+    pub fn pop_char(&mut self) -> bool {
+        if let Some(step) = self.steps.pop() {
+            if !matches!(step, SearchStep::Skipped) {
+                self.prefix.pop();
+            }
+        }
+        self.current_node().is_some()
+    }
+
+    /// Current input without whitespace, uppercased
+    #[napi]
+    pub fn prefix(&self) -> String {
+        self.prefix.clone()
+    }
+
+    /// List up to `limit` distinct postcodes starting with the input, in order
+    #[napi]
+    pub fn results(&self, limit: u32) -> Vec<String> {
+        let mut postcodes = Vec::new();
+        if let Some(addr) = self.current_node() {
+            let mut key = self.prefix.as_bytes().to_vec();
+            collect_postcodes(
+                self.data.fst_map.as_fst(),
+                addr,
+                &mut key,
+                limit as usize,
+                &mut postcodes,
+            );
+        }
+        postcodes
+    }
+
+    /// Node reached by the input, or `None` once it stopped matching
+    fn current_node(&self) -> Option<fst::raw::CompiledAddr> {
+        let last = self
+            .steps
+            .iter()
+            .rev()
+            .find(|step| !matches!(step, SearchStep::Skipped));
+        match last {
+            None => Some(self.data.fst_map.as_fst().root().addr()),
+            Some(SearchStep::Matched(addr)) => Some(*addr),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Size of one record in the `lookup_binary` input buffer
+const BINARY_QUERY_LEN: usize = 10;
+
+/// Fewest records per `lookup_binary` worker thread; smaller buffers are
+/// resolved on the calling thread, where spawning would cost more than it saves
+const BINARY_MIN_CHUNK_RECORDS: usize = 4096;
+
+/// Lookup many postcodes encoded as a packed binary buffer
+///
+/// Input: a sequence of 10-byte records, each a 6-byte ASCII postcode
+/// (e.g. `1012AB`) followed by the house number as a little-endian `u32`.
+///
+/// Output: one record per input record, in the same order. Each output record
+/// starts with a status byte: `0` when nothing was found, `1` when found. A
+/// found record continues with the street and the city, each encoded as a
+/// little-endian `u16` byte length followed by that many UTF-8 bytes.
+///
+/// Large buffers are split across the available cores, in chunks of at least
+/// `BINARY_MIN_CHUNK_RECORDS` records.
+#[napi]
+pub fn lookup_binary(queries: Buffer) -> Result<Buffer> {
+    let input: &[u8] = &queries;
+    if !input.len().is_multiple_of(BINARY_QUERY_LEN) {
+        return Err(Error::new(
+            Status::InvalidArg,
+            format!(
+                "Query buffer length {} is not a multiple of {} bytes",
+                input.len(),
+                BINARY_QUERY_LEN
+            ),
+        ));
+    }
+    if input.is_empty() {
+        return Ok(Vec::new().into());
+    }
+    let data = loaded_data()?;
+
+    let records = input.len() / BINARY_QUERY_LEN;
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(records / BINARY_MIN_CHUNK_RECORDS)
+        .max(1);
+    if threads == 1 {
+        return Ok(encode_binary_results(data, input)?.into());
+    }
+    let chunk_len = records.div_ceil(threads) * BINARY_QUERY_LEN;
+
+    let output = std::thread::scope(|scope| {
+        let workers: Vec<_> = input
+            .chunks(chunk_len)
+            .map(|chunk| scope.spawn(move || encode_binary_results(data, chunk)))
+            .collect();
+        let mut output = Vec::new();
+        for worker in workers {
+            output.extend(worker.join().expect("lookup worker panicked")?);
+        }
+        Ok::<_, Error>(output)
+    })?;
+    Ok(output.into())
+}
+
+// === Internal implementation (same as in the main server) ===
+
+/// The loaded data, loading the default data on first use
+fn loaded_data() -> Result<&'static PostcodeData> {
+    match POSTCODE_DATA.get() {
+        Some(data) => Ok(data),
+        None => load_default_data(DEFAULT_OVERRIDE_FILE_NAME),
+    }
+}
+
+/// Load the override file next to the module if present, else the embedded data
+fn load_default_data(file_name: &str) -> Result<&'static PostcodeData> {
+    match module_dir().map(|dir| dir.join(file_name)) {
+        Some(path) if path.is_file() => {
+            let compressed = read_data_file(&path)?;
+            let data = parse_data(&compressed, None, DataOrigin::File(path))?;
+            Ok(POSTCODE_DATA.get_or_init(|| data))
+        }
+        _ => load_embedded_data(),
+    }
+}
+
+#[cfg(not(feature = "no-embedded-data"))]
+fn load_embedded_data() -> Result<&'static PostcodeData> {
+    // The embedded data is validated at build time
+    Ok(POSTCODE_DATA.get_or_init(|| {
+        parse_data(COMPRESSED_DATA, Some(Codec::Brotli), DataOrigin::Embedded)
+            .expect("Embedded postcode data is corrupted")
+    }))
+}
+
+#[cfg(feature = "no-embedded-data")]
+fn load_embedded_data() -> Result<&'static PostcodeData> {
+    Err(Error::new(
+        Status::GenericFailure,
+        "NotInitialized: this build has no embedded data, call initFromFile or initFromBuffer first"
+            .to_string(),
+    ))
+}
+
+fn install_data(data: PostcodeData) -> Result<()> {
+    POSTCODE_DATA.set(data).map_err(|_| {
+        Error::new(
+            Status::GenericFailure,
+            "Postcode data is already initialized".to_string(),
+        )
+    })
+}
+
+fn read_data_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| {
+        Error::new(
+            Status::InvalidArg,
+            format!("Could not read data file {}: {}", path.display(), e),
+        )
+    })
+}
+
+/// Directory containing this native module
+#[cfg(unix)]
+fn module_dir() -> Option<PathBuf> {
+    use std::ffi::{CStr, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+
+    // SAFETY: dladdr only inspects the loaded images; `info` is plain data
+    // and `dli_fname` is checked for null before being read.
+    let file_name = unsafe {
+        let mut info: libc::Dl_info = std::mem::zeroed();
+        if libc::dladdr(module_dir as *const libc::c_void, &mut info) == 0
+            || info.dli_fname.is_null()
+        {
+            return None;
+        }
+        CStr::from_ptr(info.dli_fname)
+    };
+    Path::new(OsStr::from_bytes(file_name.to_bytes()))
+        .parent()
+        .map(Path::to_path_buf)
+}
+
+/// Directory containing this native module
+#[cfg(windows)]
+fn module_dir() -> Option<PathBuf> {
+    use std::ffi::{c_void, OsString};
+    use std::os::windows::ffi::OsStringExt;
+
+    const GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS: u32 = 0x4;
+    const GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT: u32 = 0x2;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleExW(flags: u32, module_name: *const u16, module: *mut *mut c_void)
+            -> i32;
+        fn GetModuleFileNameW(module: *mut c_void, file_name: *mut u16, size: u32) -> u32;
+    }
+
+    // SAFETY: with FROM_ADDRESS the "name" is an address inside this module,
+    // and UNCHANGED_REFCOUNT means the handle needs no release. The file name
+    // is written into `buffer`, whose length is passed along.
+    let mut buffer = vec![0u16; 1024];
+    let len = unsafe {
+        let mut module = std::ptr::null_mut();
+        if GetModuleHandleExW(
+            GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+            module_dir as *const u16,
+            &mut module,
+        ) == 0
+        {
+            return None;
+        }
+        GetModuleFileNameW(module, buffer.as_mut_ptr(), buffer.len() as u32) as usize
+    };
+    // 0 is a failure, a full buffer a truncated path
+    if len == 0 || len >= buffer.len() {
+        return None;
+    }
+    PathBuf::from(OsString::from_wide(&buffer[..len]))
+        .parent()
+        .map(Path::to_path_buf)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn module_dir() -> Option<PathBuf> {
+    None
+}
+
+fn parse_data(compressed: &[u8], codec: Option<Codec>, origin: DataOrigin) -> Result<PostcodeData> {
+    let started = Instant::now();
+    let decompressed_data = decompress(compressed, codec.unwrap_or(Codec::detect(compressed)))?;
+    let decompressed = Instant::now();
+
+    let Payload {
+        fst_bytes,
+        house_data,
+        kinds,
+    } = split_payload(&decompressed_data)?;
+
+    let fst_started = Instant::now();
+    let fst_map = Map::new(fst_bytes).map_err(|e| data_corrupted(format!("invalid FST: {}", e)))?;
+    let finished = Instant::now();
+
+    Ok(PostcodeData {
+        fst_map,
+        house_data,
+        kinds,
+        compressed_len: compressed.len(),
+        decompressed_len: decompressed_data.len(),
+        origin,
+        fingerprint: OnceLock::new(),
+        postcode_bounds: OnceLock::new(),
+        fst_stats: OnceLock::new(),
+        known_postcodes: OnceLock::new(),
+        street_index: OnceLock::new(),
+        load_timings: LoadTimings {
+            decompress_ms: elapsed_ms(started, decompressed),
+            fst_build_ms: elapsed_ms(fst_started, finished),
+            total_ms: elapsed_ms(started, finished),
+        },
+    })
+}
+
+/// The sections of a decompressed payload
+struct Payload {
+    fst_bytes: Vec<u8>,
+    house_data: Vec<Vec<u8>>,
+    kinds: Option<Vec<u8>>,
+}
+
+fn decompress(compressed: &[u8], codec: Codec) -> Result<Vec<u8>> {
+    let mut decompressed_data = Vec::new();
+    match codec {
+        Codec::Brotli => {
+            Decompressor::new(compressed, 4096)
+                .read_to_end(&mut decompressed_data)
+                .map_err(|e| data_corrupted(format!("could not decompress brotli data: {}", e)))?;
+        }
+        #[cfg(feature = "gzip")]
+        Codec::Gzip => {
+            flate2::read::GzDecoder::new(compressed)
+                .read_to_end(&mut decompressed_data)
+                .map_err(|e| data_corrupted(format!("could not decompress gzip data: {}", e)))?;
+        }
+        #[cfg(not(feature = "gzip"))]
+        Codec::Gzip => {
+            return Err(Error::new(
+                Status::InvalidArg,
+                "Gzip data requires a build with the `gzip` feature".to_string(),
+            ));
+        }
+    }
+    Ok(decompressed_data)
+}
+
+/// Split a decompressed payload into its FST, house-data shards and kinds
+///
+/// Legacy payloads are `[fst_len u64][house_len u64][fst][house_data]`.
+/// Versioned payloads start with `DATA_MAGIC` and a `u32` version; version 2
+/// continues with `[fst_len u64][shard_count u32][shard_len u64]...`, followed
+/// by the FST and then each shard in order. Version 3 adds `[kinds_len u64]`
+/// to the header and a kinds section after the shards: 7-byte records of a
+/// postcode and a `PostcodeKind` byte, sorted by postcode.
+fn split_payload(payload: &[u8]) -> Result<Payload> {
+    let mut header = HeaderReader { payload, pos: 0 };
+    let (fst_len, shard_lens, kinds_len) = if payload.starts_with(DATA_MAGIC) {
+        header.pos = DATA_MAGIC.len();
+        let version = header.u32()?;
+        if version != DATA_VERSION_SHARDED && version != DATA_VERSION_KINDS {
+            return Err(data_corrupted(format!(
+                "unsupported data format version {}",
+                version
+            )));
+        }
+        let fst_len = header.u64()?;
+        let shard_count = header.u32()?;
+        let shard_lens = (0..shard_count)
+            .map(|_| header.u64())
+            .collect::<Result<Vec<_>>>()?;
+        let kinds_len = if version == DATA_VERSION_KINDS {
+            Some(header.u64()?)
+        } else {
+            None
+        };
+        (fst_len, shard_lens, kinds_len)
+    } else {
+        let fst_len = header.u64()?;
+        (fst_len, vec![header.u64()?], None)
+    };
+
+    let fst_start = header.pos;
+    let mut end = fst_start.saturating_add(fst_len);
+    let mut bounds = Vec::with_capacity(shard_lens.len());
+    for len in shard_lens {
+        let start = end;
+        end = start.saturating_add(len);
+        bounds.push(start..end);
+    }
+    let kinds_start = end;
+    end = end.saturating_add(kinds_len.unwrap_or(0));
+    if end > payload.len() {
+        return Err(data_corrupted(format!(
+            "header declares {} bytes but the payload has {}",
+            end,
+            payload.len()
+        )));
+    }
+
+    let fst_bytes = payload[fst_start..fst_start + fst_len].to_vec();
+    let house_data = bounds
+        .into_iter()
+        .map(|range| payload[range].to_vec())
+        .collect();
+    let kinds = kinds_len.map(|_| payload[kinds_start..end].to_vec());
+    if let Some(kinds) = &kinds {
+        if kinds.len() % KIND_RECORD_LEN != 0 {
+            return Err(data_corrupted("truncated kinds section".to_string()));
+        }
+        let records: Vec<&[u8]> = kinds.chunks_exact(KIND_RECORD_LEN).collect();
+        if records.windows(2).any(|pair| pair[0][..6] >= pair[1][..6]) {
+            return Err(data_corrupted("kinds section is not sorted".to_string()));
+        }
+    }
+    Ok(Payload {
+        fst_bytes,
+        house_data,
+        kinds,
+    })
+}
+
+/// Write a payload in the layout `split_payload` reads back
+fn write_payload(data: &PostcodeData, out: &mut impl Write) -> std::io::Result<()> {
+    let fst_bytes = data.fst_map.as_fst().as_bytes();
+    match (data.house_data.as_slice(), &data.kinds) {
+        ([segment], None) => {
+            out.write_all(&(fst_bytes.len() as u64).to_le_bytes())?;
+            out.write_all(&(segment.len() as u64).to_le_bytes())?;
+        }
+        (shards, kinds) => {
+            let version = match kinds {
+                Some(_) => DATA_VERSION_KINDS,
+                None => DATA_VERSION_SHARDED,
+            };
+            out.write_all(DATA_MAGIC)?;
+            out.write_all(&version.to_le_bytes())?;
+            out.write_all(&(fst_bytes.len() as u64).to_le_bytes())?;
+            out.write_all(&(shards.len() as u32).to_le_bytes())?;
+            for shard in shards {
+                out.write_all(&(shard.len() as u64).to_le_bytes())?;
+            }
+            if let Some(kinds) = kinds {
+                out.write_all(&(kinds.len() as u64).to_le_bytes())?;
+            }
+        }
+    }
+    out.write_all(fst_bytes)?;
+    for segment in &data.house_data {
+        out.write_all(segment)?;
+    }
+    if let Some(kinds) = &data.kinds {
+        out.write_all(kinds)?;
+    }
+    Ok(())
+}
+
+/// Reads little-endian header fields, failing on a truncated header
+struct HeaderReader<'a> {
+    payload: &'a [u8],
+    pos: usize,
+}
+
+impl HeaderReader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .payload
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| data_corrupted("missing header".to_string()))?;
+        self.pos += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<usize> {
+        self.bytes().map(|bytes| u64::from_le_bytes(bytes) as usize)
+    }
+}
+
+fn data_corrupted(reason: String) -> Error {
+    Error::new(Status::GenericFailure, format!("DataCorrupted: {}", reason))
+}
+
+fn elapsed_ms(from: Instant, to: Instant) -> f64 {
+    (to - from).as_secs_f64() * 1000.0
+}
+
+fn lookup_address_fst(
+    data: &PostcodeData,
+    postcode: &str,
+    house_number: u32,
+    fields: u32,
+) -> Result<Option<LookupResult>> {
+    let postcode_upper = canonical_postcode(postcode);
+    let prefix = format!("{}|", postcode_upper);
+    let strict = STRICT_MODE.load(Ordering::Relaxed);
+
+    let policy = AmbiguityPolicy::current();
+
+    let automaton = Str::new(&prefix).starts_with();
+    let mut stream = data.fst_map.search(automaton).into_stream();
+
+    // (straat, woonplaats, house-number count) of the match chosen so far
+    let mut chosen: Option<(String, String, usize)> = None;
+    while let Some((key_bytes, offset)) = stream.next() {
+        let (_, straat, woonplaats) = match split_key(key_bytes) {
+            Some(parts) => parts,
+            None if strict => return Err(malformed_key_error(key_bytes)),
+            None => continue,
+        };
+        let house_numbers_compressed = data.strict_block(offset, strict)?;
+        if !block_contains(house_numbers_compressed, house_number) {
+            continue;
+        }
+        let count = block_count(house_numbers_compressed);
+        match (policy, &chosen) {
+            (AmbiguityPolicy::FirstMatch, _) => {
+                return Ok(Some(LookupResult::new_selected(
+                    data,
+                    postcode_upper,
+                    straat,
+                    house_number,
+                    woonplaats,
+                    fields,
+                )));
+            }
+            (AmbiguityPolicy::Error, Some((first_straat, first_woonplaats, _))) => {
+                return Err(Error::new(
+                    Status::GenericFailure,
+                    format!(
+                        "AmbiguousAddress: {} {} matches both {}, {} and {}, {}",
+                        postcode_upper,
+                        house_number,
+                        first_straat,
+                        first_woonplaats,
+                        straat,
+                        woonplaats
+                    ),
+                ));
+            }
+            (AmbiguityPolicy::MostNumbers, Some((_, _, best))) if count <= *best => {}
+            _ => chosen = Some((straat.to_string(), woonplaats.to_string(), count)),
+        }
+    }
+    Ok(chosen.map(|(straat, woonplaats, _)| {
+        LookupResult::new_selected(
+            data,
+            postcode_upper,
+            &straat,
+            house_number,
+            &woonplaats,
+            fields,
+        )
+    }))
+}
+
+/// `expected_straat` and `expected_woonplaats` must already be lowercased
+fn validate_address_fst(
+    data: &PostcodeData,
+    postcode: &str,
+    house_number: u32,
+    expected_straat: &str,
+    expected_woonplaats: &str,
+) -> Result<AddressValidation> {
+    let postcode_upper = canonical_postcode(postcode);
+    let strict = STRICT_MODE.load(Ordering::Relaxed);
+    let mut stream = data
+        .fst_map
+        .range()
+        .ge(format!("{}|", postcode_upper))
+        .lt(format!("{}}}", postcode_upper))
+        .into_stream();
+
+    let mut validation = AddressValidation {
+        postcode_exists: false,
+        house_number_exists: false,
+        street_matches: false,
+        city_matches: false,
+        valid: false,
+    };
+    while let Some((key_bytes, offset)) = stream.next() {
+        let (_, straat, woonplaats) = match split_key(key_bytes) {
+            Some(parts) => parts,
+            None if strict => return Err(malformed_key_error(key_bytes)),
+            None => continue,
+        };
+        let has_number = block_contains(data.strict_block(offset, strict)?, house_number);
+        let street_matches = straat.to_lowercase() == expected_straat;
+        let city_matches = woonplaats.to_lowercase() == expected_woonplaats;
+        validation.postcode_exists = true;
+        validation.house_number_exists |= has_number;
+        validation.street_matches |= street_matches;
+        validation.city_matches |= city_matches;
+        validation.valid |= has_number && street_matches && city_matches;
+    }
+    Ok(validation)
+}
+
+fn diff_fst(old: &PostcodeData, new: &PostcodeData, limit: usize) -> DatasetDiff {
+    let mut diff = DatasetDiff {
+        added: 0,
+        removed: 0,
+        changed: 0,
+        unchanged: 0,
+        added_keys: Vec::new(),
+        removed_keys: Vec::new(),
+        changed_keys: Vec::new(),
+    };
+    let note = |keys: &mut Vec<String>, key: &[u8]| {
+        if keys.len() < limit {
+            keys.push(String::from_utf8_lossy(key).into_owned());
+        }
+    };
+    let mut union = old.fst_map.op().add(&new.fst_map).union();
+    while let Some((key_bytes, values)) = union.next() {
+        // The union does not order the values of a key by stream
+        let value_of = |index| values.iter().find(|v| v.index == index).map(|v| v.value);
+        match (value_of(0), value_of(1)) {
+            (Some(old_value), Some(new_value)) => {
+                let old_numbers = decompress_house_numbers(old.block(old_value));
+                let new_numbers = decompress_house_numbers(new.block(new_value));
+                if old_numbers == new_numbers {
+                    diff.unchanged += 1;
+                } else {
+                    diff.changed += 1;
+                    note(&mut diff.changed_keys, key_bytes);
+                }
+            }
+            (Some(_), None) => {
+                diff.removed += 1;
+                note(&mut diff.removed_keys, key_bytes);
+            }
+            _ => {
+                diff.added += 1;
+                note(&mut diff.added_keys, key_bytes);
+            }
+        }
+    }
+    diff
+}
+
+fn try_lookup_fst(data: &PostcodeData, postcode: &str, house_number: u32) -> Result<LookupOutcome> {
+    let postcode_upper = canonical_postcode(postcode);
+    let strict = STRICT_MODE.load(Ordering::Relaxed);
+    let mut stream = data
+        .fst_map
+        .range()
+        .ge(format!("{}|", postcode_upper))
+        .lt(format!("{}}}", postcode_upper))
+        .into_stream();
+
+    let mut range: Option<(u32, u32)> = None;
+    // Street whose block range lies closest to the number, with that distance
+    let mut nearest: Option<(u32, StreetCity)> = None;
+    while let Some((key_bytes, offset)) = stream.next() {
+        let (_, straat, woonplaats) = match split_key(key_bytes) {
+            Some(parts) => parts,
+            None if strict => return Err(malformed_key_error(key_bytes)),
+            None => continue,
+        };
+        let mut numbers = HouseNumberIter::new(data.strict_block(offset, strict)?);
+        let Some(first) = numbers.next() else {
+            continue;
+        };
+        let mut last = first;
+        let mut found = first == house_number;
+        for n in numbers {
+            found |= n == house_number;
+            last = n;
+        }
+        if found {
+            return Ok(LookupOutcome {
+                status: LookupStatus::Found,
+                result: Some(LookupResult::new(
+                    data,
+                    postcode_upper,
+                    straat,
+                    house_number,
+                    woonplaats,
+                )),
+                nearest_street: None,
+                min_huisnummer: None,
+                max_huisnummer: None,
+            });
+        }
+        let distance = if house_number < first {
+            first - house_number
+        } else {
+            house_number.saturating_sub(last)
+        };
+        if nearest.as_ref().is_none_or(|(best, _)| distance < *best) {
+            let street = StreetCity {
+                straat: straat.to_string(),
+                woonplaats: woonplaats.to_string(),
+            };
+            nearest = Some((distance, street));
+        }
+        range = Some(match range {
+            Some((min, max)) => (min.min(first), max.max(last)),
+            None => (first, last),
+        });
+    }
+
+    let Some((min, max)) = range else {
+        return Ok(LookupOutcome {
+            status: LookupStatus::UnknownPostcode,
+            result: None,
+            nearest_street: None,
+            min_huisnummer: None,
+            max_huisnummer: None,
+        });
+    };
+    let status = if house_number < min || house_number > max {
+        LookupStatus::OutOfRange
+    } else {
+        LookupStatus::NotFound
+    };
+    Ok(LookupOutcome {
+        status,
+        result: None,
+        nearest_street: nearest.map(|(_, street)| street),
+        min_huisnummer: Some(min),
+        max_huisnummer: Some(max),
+    })
+}
+
+/// Canonical form of a postcode as stored in the keys: uppercased, without
+/// whitespace, so `" 1012 ab"` becomes `"1012AB"`
+fn canonical_postcode(postcode: &str) -> String {
+    postcode
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Split a `postcode|straat|woonplaats` key, or `None` if it is malformed
+fn split_key(key_bytes: &[u8]) -> Option<(&str, &str, &str)> {
+    let key_str = std::str::from_utf8(key_bytes).ok()?;
+    let mut parts = key_str.split('|');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(postcode), Some(straat), Some(woonplaats), None) => {
+            Some((postcode, straat, woonplaats))
+        }
+        _ => None,
+    }
+}
+
+fn malformed_key_error(key_bytes: &[u8]) -> Error {
+    data_corrupted(format!(
+        "malformed key {:?}",
+        String::from_utf8_lossy(key_bytes)
+    ))
+}
+
+/// Streams the keys of one postcode and yields each street whose block
+/// contains the house number
+struct AddressMatches<'a> {
+    data: &'a PostcodeData,
+    stream: fst::map::Stream<'a>,
+    postcode: String,
+    house_number: u32,
+    strict: bool,
+    /// Keys read from the stream so far
+    examined: u32,
+}
+
+impl<'a> AddressMatches<'a> {
+    fn new(data: &'a PostcodeData, postcode: &str, house_number: u32) -> Self {
+        let postcode_upper = canonical_postcode(postcode);
+        // Every key of the postcode sorts between "<postcode>|" and "<postcode>}"
+        let stream = data
+            .fst_map
+            .range()
+            .ge(format!("{}|", postcode_upper))
+            .lt(format!("{}}}", postcode_upper))
+            .into_stream();
+        AddressMatches {
+            data,
+            stream,
+            postcode: postcode_upper,
+            house_number,
+            strict: STRICT_MODE.load(Ordering::Relaxed),
+            examined: 0,
+        }
+    }
+}
+
+impl Iterator for AddressMatches<'_> {
+    /// A match, or in strict mode the `DataCorrupted` error that ends the stream
+    type Item = Result<LookupResult>;
+
+    fn next(&mut self) -> Option<Result<LookupResult>> {
+        while let Some((key_bytes, offset)) = self.stream.next() {
+            self.examined += 1;
+            let (_, straat, woonplaats) = match split_key(key_bytes) {
+                Some(parts) => parts,
+                None if self.strict => return Some(Err(malformed_key_error(key_bytes))),
+                None => continue,
+            };
+            let block = match self.data.strict_block(offset, self.strict) {
+                Ok(block) => block,
+                Err(e) => return Some(Err(e)),
+            };
+            if block_contains(block, self.house_number) {
+                return Some(Ok(LookupResult::new(
+                    self.data,
+                    self.postcode.clone(),
+                    straat,
+                    self.house_number,
+                    woonplaats,
+                )));
+            }
+        }
+        None
+    }
+}
+
+/// One street of a postcode with its decompressed house numbers
+struct StreetBlock {
+    straat: String,
+    woonplaats: String,
+    house_numbers: Vec<u32>,
+}
+
+/// Decompress the house-number blocks of every street in an uppercased postcode
+///
+/// In strict mode a malformed key or block is a `DataCorrupted` error.
+fn postcode_blocks(data: &PostcodeData, postcode_upper: &str) -> Result<Vec<StreetBlock>> {
+    let prefix = format!("{}|", postcode_upper);
+    let strict = STRICT_MODE.load(Ordering::Relaxed);
+    let automaton = Str::new(&prefix).starts_with();
+    let mut stream = data.fst_map.search(automaton).into_stream();
+
+    let mut blocks = Vec::new();
+    while let Some((key_bytes, offset)) = stream.next() {
+        let (_, straat, woonplaats) = match split_key(key_bytes) {
+            Some(parts) => parts,
+            None if strict => return Err(malformed_key_error(key_bytes)),
+            None => continue,
+        };
+        blocks.push(StreetBlock {
+            straat: straat.to_string(),
+            woonplaats: woonplaats.to_string(),
+            house_numbers: decompress_house_numbers(data.strict_block(offset, strict)?),
+        });
+    }
+    Ok(blocks)
+}
+
+fn lookup_postcode_fst(data: &PostcodeData, postcode: &str) -> Result<Option<LookupResult>> {
+    let postcode_upper = canonical_postcode(postcode);
+    let prefix = format!("{}|", postcode_upper);
+    let strict = STRICT_MODE.load(Ordering::Relaxed);
+
+    let automaton = Str::new(&prefix).starts_with();
+    let mut stream = data.fst_map.search(automaton).into_stream();
+
+    while let Some((key_bytes, offset)) = stream.next() {
+        let (_, straat, woonplaats) = match split_key(key_bytes) {
+            Some(parts) => parts,
+            None if strict => return Err(malformed_key_error(key_bytes)),
+            None => continue,
+        };
+        data.strict_block(offset, strict)?;
+        return Ok(Some(LookupResult::new(
+            data,
+            postcode_upper,
+            straat,
+            0,
+            woonplaats,
+        )));
+    }
+    Ok(None)
+}
+
+/// Reservoir sampling (algorithm R) over the whole FST stream in one pass
+fn sample_fst(data: &PostcodeData, n: usize, seed: u64) -> Result<Vec<LookupResult>> {
+    let strict = STRICT_MODE.load(Ordering::Relaxed);
+    let mut rng = SplitMix64(seed);
+    // (position in the stream, key, first house number) of each sampled key
+    let mut reservoir: Vec<(u64, Vec<u8>, u32)> = Vec::with_capacity(n);
+    let mut seen = 0u64;
+    let mut stream = data.fst_map.stream();
+    while let Some((key_bytes, offset)) = stream.next() {
+        if split_key(key_bytes).is_none() {
+            if strict {
+                return Err(malformed_key_error(key_bytes));
+            }
+            continue;
+        }
+        let Some(first) = HouseNumberIter::new(data.strict_block(offset, strict)?).next() else {
+            continue;
+        };
+        if reservoir.len() < n {
+            reservoir.push((seen, key_bytes.to_vec(), first));
+        } else {
+            let slot = rng.below(seen + 1) as usize;
+            if slot < n {
+                reservoir[slot] = (seen, key_bytes.to_vec(), first);
+            }
+        }
+        seen += 1;
+    }
+
+    reservoir.sort_unstable_by_key(|(position, _, _)| *position);
+    Ok(reservoir
+        .iter()
+        .filter_map(|(_, key_bytes, first)| {
+            let (postcode, straat, woonplaats) = split_key(key_bytes)?;
+            Some(LookupResult::new(
+                data,
+                postcode.to_string(),
+                straat,
+                *first,
+                woonplaats,
+            ))
+        })
+        .collect())
+}
+
+/// SplitMix64, a small seeded generator with a fixed, portable output stream
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..bound`, by multiply-shift of a 64-bit draw
+    fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+fn relax_to_area_fst(
+    data: &PostcodeData,
+    postcode: &str,
+    house_number: u32,
+) -> Option<LookupResult> {
+    let postcode_upper = canonical_postcode(postcode);
+    if !is_valid_format(postcode_upper.clone()) {
+        return None;
+    }
+    let (area, letters) = postcode_upper.split_at(4);
+    let letters_rank = |letters: &str| {
+        letters
+            .bytes()
+            .fold(0i32, |rank, letter| rank * 26 + i32::from(letter - b'A'))
+    };
+    let target = letters_rank(letters);
+
+    // Closest (distance, postcode, straat, woonplaats) with the number, and with any number
+    let mut with_number: Option<(i32, String, String, String)> = None;
+    let mut any_number: Option<(i32, String, String, String)> = None;
+    let mut stream = data
+        .fst_map
+        .search(Str::new(area).starts_with())
+        .into_stream();
+    while let Some((key_bytes, offset)) = stream.next() {
+        let Some((candidate, straat, woonplaats)) = split_key(key_bytes) else {
+            continue;
+        };
+        let candidate_letters = &candidate[area.len().min(candidate.len())..];
+        if candidate == postcode_upper
+            || candidate_letters.len() != 2
+            || !candidate_letters.bytes().all(|b| b.is_ascii_uppercase())
+        {
+            continue;
+        }
+        let distance = (letters_rank(candidate_letters) - target).abs();
+        let entry = || {
+            (
+                distance,
+                candidate.to_string(),
+                straat.to_string(),
+                woonplaats.to_string(),
+            )
+        };
+        if any_number.as_ref().is_none_or(|best| distance < best.0) {
+            any_number = Some(entry());
+        }
+        let closer = with_number.as_ref().is_none_or(|best| distance < best.0);
+        if closer && block_contains(data.block(offset), house_number) {
+            with_number = Some(entry());
+        }
+    }
+
+    match (with_number, any_number) {
+        (Some((_, postcode, straat, woonplaats)), _) => Some(LookupResult::new(
+            data,
+            postcode,
+            &straat,
+            house_number,
+            &woonplaats,
+        )),
+        (None, Some((_, postcode, straat, woonplaats))) => {
+            Some(LookupResult::new(data, postcode, &straat, 0, &woonplaats))
+        }
+        (None, None) => None,
+    }
+}
+
+fn suggest_postcodes_fst(data: &PostcodeData, postcode: &str, limit: usize) -> Vec<String> {
+    let postcode_upper = canonical_postcode(postcode);
+    let prefix = format!("{}|", postcode_upper);
+    // The automaton accepts every key with a prefix close to "<postcode>|";
+    // the exact distance is then recomputed on the postcode part alone.
+    let Ok(levenshtein) = Levenshtein::new(&prefix, MAX_SUGGEST_DISTANCE) else {
+        return Vec::new();
+    };
+    let mut stream = data.fst_map.search(levenshtein.starts_with()).into_stream();
+
+    let mut candidates = Vec::new();
+    let mut seen = HashSet::new();
+    while let Some((key_bytes, _)) = stream.next() {
+        let key_str = std::str::from_utf8(key_bytes).unwrap_or("");
+        let Some((candidate, _)) = key_str.split_once('|') else {
+            continue;
+        };
+        if !seen.insert(candidate.to_string()) {
+            continue;
+        }
+        let distance = edit_distance(&postcode_upper, candidate);
+        if distance <= MAX_SUGGEST_DISTANCE {
+            candidates.push((distance, candidate.to_string()));
+        }
+    }
+    // Explicit (distance, postcode) order; never depends on stream or hash order
+    candidates.sort_unstable_by(|(distance_a, postcode_a), (distance_b, postcode_b)| {
+        distance_a
+            .cmp(distance_b)
+            .then_with(|| postcode_a.as_bytes().cmp(postcode_b.as_bytes()))
+    });
+    candidates
+        .into_iter()
+        .take(limit)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> u32 {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<u32> = (0..=b.len() as u32).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i as u32 + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + u32::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn nearest_key_fst(data: &PostcodeData, postcode: &str) -> Option<String> {
+    let postcode_upper = canonical_postcode(postcode);
+    let query = postcode_upper.as_bytes();
+
+    let after = data
+        .fst_map
+        .range()
+        .ge(query)
+        .into_stream()
+        .next()
+        .map(|(key_bytes, _)| key_bytes.to_vec());
+    let before = predecessor_key(data.fst_map.as_fst(), query);
+
+    let common_prefix = |key: &[u8]| key.iter().zip(query).take_while(|(a, b)| a == b).count();
+    match (before, after) {
+        (Some(before), Some(after)) if common_prefix(&before) > common_prefix(&after) => {
+            Some(postcode_of_key(&before))
+        }
+        (before, after) => after.or(before).map(|key| postcode_of_key(&key)),
+    }
+}
+
+/// The postcode component of a key, or the whole key if it has no `|`
+fn postcode_of_key(key: &[u8]) -> String {
+    let key_str = String::from_utf8_lossy(key);
+    match key_str.split_once('|') {
+        Some((postcode, _)) => postcode.to_string(),
+        None => key_str.into_owned(),
+    }
+}
+
+/// Find the largest key strictly smaller than `query`
+///
+/// The FST map only streams forwards, so this walks the query's path and
+/// remembers the deepest point where a smaller branch (or a shorter key) exists.
+fn predecessor_key(fst: &fst::raw::Fst<Vec<u8>>, query: &[u8]) -> Option<Vec<u8>> {
+    let mut node = fst.root();
+    let mut best = None;
+    for (depth, &byte) in query.iter().enumerate() {
+        let smaller = node.transitions().filter(|t| t.inp < byte).last();
+        if let Some(transition) = smaller {
+            let mut key = query[..depth].to_vec();
+            key.push(transition.inp);
+            best = Some(max_key_from(fst, fst.node(transition.addr), key));
+        } else if node.is_final() {
+            best = Some(query[..depth].to_vec());
+        }
+        match node.find_input(byte) {
+            Some(i) => node = fst.node(node.transition(i).addr),
+            None => return best,
+        }
+    }
+    best
+}
+
+/// Collect the postcodes of the keys below `addr` in key order, up to `limit`
+///
+/// `key` holds the bytes leading to `addr`; a `|` transition ends a postcode,
+/// so the street and city parts of the keys are never visited.
+fn collect_postcodes(
+    fst: &fst::raw::Fst<Vec<u8>>,
+    addr: fst::raw::CompiledAddr,
+    key: &mut Vec<u8>,
+    limit: usize,
+    postcodes: &mut Vec<String>,
+) {
+    for transition in fst.node(addr).transitions() {
+        if postcodes.len() >= limit {
+            return;
+        }
+        if transition.inp == b'|' {
+            postcodes.push(String::from_utf8_lossy(key).into_owned());
+            continue;
+        }
+        key.push(transition.inp);
+        collect_postcodes(fst, transition.addr, key, limit, postcodes);
+        key.pop();
+    }
+}
+
+/// Extend `key` with the largest key in the subtree rooted at `node`
+fn max_key_from<'f>(
+    fst: &'f fst::raw::Fst<Vec<u8>>,
+    mut node: fst::raw::Node<'f>,
+    mut key: Vec<u8>,
+) -> Vec<u8> {
+    while !node.is_empty() {
+        let transition = node.transition(node.len() - 1);
+        key.push(transition.inp);
+        node = fst.node(transition.addr);
+    }
+    key
+}
+
+fn street_city_pairs_fst(data: &PostcodeData, prefix: &str, limit: usize) -> StreetCityPairsResult {
+    let prefix_upper = canonical_postcode(prefix);
+    let automaton = Str::new(&prefix_upper).starts_with();
+    let mut stream = data.fst_map.search(automaton).into_stream();
+
+    let mut seen = HashSet::new();
+    let mut pairs = Vec::new();
+    let mut examined = 0;
+    let mut truncated = false;
+    while let Some((key_bytes, _)) = stream.next() {
+        examined += 1;
+        let key_str = std::str::from_utf8(key_bytes).unwrap_or("");
+        let parts: Vec<&str> = key_str.split('|').collect();
+        if parts.len() == 3 && seen.insert((parts[1].to_string(), parts[2].to_string())) {
+            // One pair past the limit is enough to know the output was cut short
+            if pairs.len() == limit {
+                truncated = true;
+                break;
+            }
+            pairs.push(StreetCity {
+                straat: parts[1].to_string(),
+                woonplaats: parts[2].to_string(),
+            });
+        }
+    }
+    StreetCityPairsResult {
+        items: pairs,
+        truncated,
+        total_examined: examined,
+    }
+}
+
+fn decompress_house_numbers(compressed_data: &[u8]) -> Vec<u32> {
+    HouseNumberIter::new(compressed_data).collect()
+}
+
+/// Number of house numbers in a block, read from its header alone
+fn block_count(compressed_data: &[u8]) -> usize {
+    match compressed_data {
+        [lo, hi, ..] => (u16::from_le_bytes([*lo, *hi]) & !BLOCK_TAGGED) as usize,
+        _ => 0,
+    }
+}
+
+/// Checks block membership, decoding only up to the target number
+fn block_contains(compressed_data: &[u8], house_number: u32) -> bool {
+    HouseNumberIter::new(compressed_data)
+        .take_while(|&n| n <= house_number)
+        .any(|n| n == house_number)
+}
+
+/// Block header bit marking that an encoding tag byte follows the count
+///
+/// This caps a block at 32,767 house numbers. A legacy untagged block with a
+/// count of 0x8000 or more, which older data builds could write, is now read
+/// as tagged and decodes differently; no postcode in the bundled data comes
+/// close to that size.
+const BLOCK_TAGGED: u16 = 0x8000;
+
+/// Encoding tag: the delta format used by untagged blocks
+const BLOCK_ENCODING_DELTA: u8 = 0;
+
+/// Encoding tag: runs of consecutive numbers
+const BLOCK_ENCODING_RUNS: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockEncoding {
+    Delta,
+    Runs,
+}
+
+/// Lazily decodes a compressed block of sorted house numbers
+///
+/// A block starts with a `u16` count. When its top bit (`BLOCK_TAGGED`) is
+/// set, the low 15 bits are the count and an encoding tag byte follows;
+/// untagged blocks always use the delta encoding.
+///
+/// - Delta: the first number as `u16`, then one delta per remaining number:
+///   a single non-zero byte, or `0` followed by a `u16`.
+/// - Runs: `(u16 start, u16 length)` pairs of consecutive numbers, until
+///   `count` numbers have been produced. `1..=200` takes 7 bytes this way
+///   instead of 203 as deltas.
+struct HouseNumberIter<'a> {
+    data: &'a [u8],
+    encoding: BlockEncoding,
+    remaining: usize,
+    last: Option<u32>,
+    run_left: u16,
+}
+
+impl<'a> HouseNumberIter<'a> {
+    fn new(compressed_data: &'a [u8]) -> Self {
+        let mut iter = HouseNumberIter {
+            data: compressed_data,
+            encoding: BlockEncoding::Delta,
+            remaining: 0,
+            last: None,
+            run_left: 0,
+        };
+        let Some(header) = iter.read_u16() else {
+            return iter;
+        };
+        if header & BLOCK_TAGGED != 0 {
+            let Some((&tag, rest)) = iter.data.split_first() else {
+                return iter;
+            };
+            iter.data = rest;
+            iter.encoding = match tag {
+                BLOCK_ENCODING_DELTA => BlockEncoding::Delta,
+                BLOCK_ENCODING_RUNS => BlockEncoding::Runs,
+                // Unknown encodings decode as an empty block
+                _ => return iter,
+            };
+        }
+        iter.remaining = (header & !BLOCK_TAGGED) as usize;
+        iter
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let (bytes, rest) = self.data.split_first_chunk::<2>()?;
+        self.data = rest;
+        Some(u16::from_le_bytes(*bytes))
+    }
+
+    fn decode_next(&mut self) -> Option<u32> {
+        match self.encoding {
+            BlockEncoding::Delta => self.decode_next_delta(),
+            BlockEncoding::Runs => self.decode_next_run(),
+        }
+    }
+
+    fn decode_next_delta(&mut self) -> Option<u32> {
+        let Some(last) = self.last else {
+            return self.read_u16().map(u32::from);
+        };
+        let (&delta_marker, rest) = self.data.split_first()?;
+        self.data = rest;
+        let delta = if delta_marker == 0 {
+            self.read_u16()? as u32
+        } else {
+            delta_marker as u32
+        };
+        // A corrupt wide delta must not wrap around; stop at what was decoded so far.
+        last.checked_add(delta)
+    }
+
+    fn decode_next_run(&mut self) -> Option<u32> {
+        if self.run_left > 0 {
+            self.run_left -= 1;
+            return self.last?.checked_add(1);
+        }
+        let start = self.read_u16()?;
+        let length = self.read_u16()?;
+        if length == 0 {
+            return None;
+        }
+        self.run_left = length - 1;
+        Some(start as u32)
+    }
+}
+
+impl Iterator for HouseNumberIter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.remaining == 0 {
+            return None;
+        }
+        match self.decode_next() {
+            Some(n) => {
+                self.remaining -= 1;
+                self.last = Some(n);
+                Some(n)
+            }
+            None => {
+                self.remaining = 0;
+                None
+            }
+        }
+    }
+}
+
+/// Words kept lowercase by the title caser, except at the start of a name
+const LOWERCASE_PARTICLES: &[&str] = &[
+    "aan", "bij", "de", "den", "der", "en", "het", "in", "onder", "op", "over", "te", "ten", "ter",
+    "van", "voor",
+];
+
+/// Title-case a Dutch place or street name
+///
+/// Particles such as "aan", "den" and "op" stay lowercase unless they start
+/// the name ("Alphen aan den Rijn", "Den Haag"). The elided articles "'s" and
+/// "'t" always stay lowercase ("'s-Gravenhage"), hyphenated parts are
+/// capitalized separately and a leading "ij" becomes "IJ" ("IJmuiden").
+fn dutch_title_case(name: &str) -> String {
+    name.to_lowercase()
+        .split(' ')
+        .enumerate()
+        .map(|(word_index, word)| {
+            word.split('-')
+                .enumerate()
+                .map(|(part_index, part)| {
+                    let starts_name = word_index == 0 && part_index == 0;
+                    let elided_article = part == "'s" || part == "'t";
+                    if elided_article || !starts_name && LOWERCASE_PARTICLES.contains(&part) {
+                        part.to_string()
+                    } else {
+                        capitalize(part)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Uppercase the first letter of a lowercase word, treating "ij" as one letter
+fn capitalize(word: &str) -> String {
+    let Some(start) = word.find(char::is_alphabetic) else {
+        return word.to_string();
+    };
+    let (prefix, rest) = word.split_at(start);
+    if let Some(tail) = rest.strip_prefix("ij") {
+        return format!("{prefix}IJ{tail}");
+    }
+    let mut chars = rest.chars();
+    let initial = chars.next().map(|c| c.to_uppercase().collect::<String>());
+    format!("{prefix}{}{}", initial.unwrap_or_default(), chars.as_str())
+}
+
+fn encode_binary_results(data: &PostcodeData, records: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(records.len() / BINARY_QUERY_LEN * 32);
+    for record in records.chunks_exact(BINARY_QUERY_LEN) {
+        let house_number = u32::from_le_bytes(record[6..10].try_into().unwrap());
+        let result = match std::str::from_utf8(&record[0..6]) {
+            Ok(postcode) => lookup_address_fst(data, postcode, house_number, FIELD_ALL)?,
+            Err(_) => None,
+        };
+        match result {
+            Some(result) => {
+                out.push(1);
+                for field in [&result.straat, &result.woonplaats] {
+                    out.extend_from_slice(&(field.len() as u16).to_le_bytes());
+                    out.extend_from_slice(field.as_bytes());
+                }
+            }
+            None => out.push(0),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Set in the fresh test binary started by `in_fresh_process`
+    const CHILD_ENV: &str = "POSTRUST_TEST_CHILD";
+
+    /// Run the test `name` alone in a fresh copy of the test binary, for tests
+    /// that need unloaded data or change global settings
+    ///
+    /// Returns true inside that copy, where the test body should run, and
+    /// false in the parent once the copy has passed.
+    fn in_fresh_process(name: &str) -> bool {
+        if std::env::var_os(CHILD_ENV).is_some() {
+            return true;
+        }
+        let child = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", &format!("tests::{name}")])
+            .env(CHILD_ENV, "1")
+            .output()
+            .unwrap();
+        assert!(
+            child.status.success(),
+            "{}",
+            String::from_utf8_lossy(&child.stdout)
+        );
+        false
+    }
+
+    /// Brotli-compressed payload of `keys` (sorted) over one house segment, in
+    /// the legacy layout or, when `kinds` records are given, format version 3
+    fn brotli_payload(keys: &[(&str, u64)], house_data: &[u8], kinds: Option<&[u8]>) -> Vec<u8> {
+        let fst_bytes = fst::Map::from_iter(keys.iter().copied())
+            .unwrap()
+            .into_fst()
+            .into_inner();
+        let mut payload = Vec::new();
+        if kinds.is_some() {
+            payload.extend_from_slice(DATA_MAGIC);
+            payload.extend_from_slice(&DATA_VERSION_KINDS.to_le_bytes());
+        }
+        payload.extend_from_slice(&(fst_bytes.len() as u64).to_le_bytes());
+        if kinds.is_some() {
+            payload.extend_from_slice(&1u32.to_le_bytes());
+        }
+        payload.extend_from_slice(&(house_data.len() as u64).to_le_bytes());
+        if let Some(kinds) = kinds {
+            payload.extend_from_slice(&(kinds.len() as u64).to_le_bytes());
+        }
+        payload.extend_from_slice(&fst_bytes);
+        payload.extend_from_slice(house_data);
+        payload.extend_from_slice(kinds.unwrap_or_default());
+        let mut compressed = Vec::new();
+        brotli::BrotliCompress(
+            &mut payload.as_slice(),
+            &mut compressed,
+            &BrotliEncoderParams::default(),
+        )
+        .unwrap();
+        compressed
+    }
+
+    #[test]
+    fn delta_overflow_stops_decoding_without_panicking() {
+        // Count 3, then the largest first number and the widest deltas
+        let widest = [3, 0, 0xFF, 0xFF, 0, 0xFF, 0xFF, 0, 0xFF, 0xFF];
+        assert_eq!(decompress_house_numbers(&widest), [65535, 131070, 196605]);
+
+        // A block that ends before its count stops after what was decoded
+        assert_eq!(
+            decompress_house_numbers(&[5, 0, 0xFF, 0xFF, 0, 0xFF]),
+            [65535]
+        );
+
+        // A wide delta past `u32::MAX` ends the block instead of wrapping
+        let mut numbers = HouseNumberIter::new(&[2, 0, 0, 0xFF, 0xFF]);
+        numbers.last = Some(u32::MAX - 1);
+        assert_eq!(numbers.next(), None);
+        assert_eq!(numbers.next(), None);
+    }
+
+    #[test]
+    fn runs_blocks_decode_like_delta_blocks() {
+        let dense: Vec<u32> = (1..=200).collect();
+
+        // Tagged header (200 | BLOCK_TAGGED), runs tag, then one (1, 200) run
+        let runs = [0xC8, 0x80, BLOCK_ENCODING_RUNS, 1, 0, 200, 0];
+        // Untagged header, first number 1, then 199 single-byte deltas of 1
+        let mut delta = vec![200, 0, 1, 0];
+        delta.extend(std::iter::repeat_n(1, 199));
+
+        assert_eq!(runs.len(), 7);
+        assert_eq!(delta.len(), 203);
+        assert_eq!(decompress_house_numbers(&runs), dense);
+        assert_eq!(decompress_house_numbers(&delta), dense);
+        assert_eq!(block_count(&runs), 200);
+
+        // A tagged block may also use the delta encoding
+        assert_eq!(
+            decompress_house_numbers(&[2, 0x80, BLOCK_ENCODING_DELTA, 4, 0, 2]),
+            [4, 6]
+        );
+    }
+
+    #[test]
+    fn multi_run_zero_length_run_and_unknown_tag_blocks() {
+        // Runs (1, 3) and (10, 2)
+        let multi = [5, 0x80, BLOCK_ENCODING_RUNS, 1, 0, 3, 0, 10, 0, 2, 0];
+        assert_eq!(decompress_house_numbers(&multi), [1, 2, 3, 10, 11]);
+        assert!(block_contains(&multi, 10));
+        assert!(!block_contains(&multi, 4));
+
+        // A zero-length run ends the block after what was decoded so far
+        let zero_length = [4, 0x80, BLOCK_ENCODING_RUNS, 1, 0, 2, 0, 9, 0, 0, 0];
+        assert_eq!(decompress_house_numbers(&zero_length), [1, 2]);
+        assert!(decompress_house_numbers(&[3, 0x80, BLOCK_ENCODING_RUNS, 5, 0, 0, 0]).is_empty());
+
+        // An unknown tag or a missing tag byte decodes as an empty block
+        assert!(decompress_house_numbers(&[2, 0x80, 7, 1, 0, 2, 0]).is_empty());
+        assert!(decompress_house_numbers(&[2, 0x80]).is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-embedded-data"))]
+    fn house_number_zero_means_any_by_default() {
+        let result = lookup("1012AB".to_string(), 0, None).unwrap().unwrap();
+        assert_eq!(result.straat, "Stationsplein");
+        assert_eq!(result.woonplaats, "Amsterdam");
+        assert_eq!(result.huisnummer, 0);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-embedded-data"))]
+    fn house_number_zero_is_literal_without_zero_means_any() {
+        assert!(lookup("1012AB".to_string(), 0, Some(false))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-embedded-data"))]
+    fn embedded_data_parses_and_resolves_an_address() {
+        let data = parse_data(COMPRESSED_DATA, Some(Codec::Brotli), DataOrigin::Embedded).unwrap();
+        let result = lookup_address_fst(&data, "1012AB", 1, FIELD_ALL)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.straat, "Stationsplein");
+        assert_eq!(result.woonplaats, "Amsterdam");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-embedded-data"))]
+    fn suggest_postcodes_is_the_same_on_every_call() {
+        let first = suggest_postcodes("1O12AB".to_string(), 20).unwrap();
+        assert_eq!(first.len(), 20);
+        assert!(first.windows(2).all(|pair| pair[0] != pair[1]));
+        for _ in 0..5 {
+            assert_eq!(suggest_postcodes("1O12AB".to_string(), 20).unwrap(), first);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-embedded-data"))]
+    fn concurrent_lookups_agree_while_info_is_read() {
+        use std::sync::{Arc, Barrier};
+
+        const LOOKUP_THREADS: usize = 8;
+        const BATCH_THREADS: usize = 4;
+        const ROUNDS: usize = 200;
+
+        // Results are compared in their JSON form, `LookupResult` having no `PartialEq`
+        let single = || serde_json::to_string(&lookup("1012AB".to_string(), 1, None).unwrap());
+        let batch = || {
+            let queries = vec![("1012AB".to_string(), 1), ("9999ZZ".to_string(), 1)];
+            serde_json::to_string(&lookup_batch(queries, None).unwrap())
+        };
+        let (expected, expected_batch) = (single().unwrap(), batch().unwrap());
+        let barrier = Arc::new(Barrier::new(LOOKUP_THREADS + BATCH_THREADS + 1));
+
+        let mut handles = Vec::new();
+        for _ in 0..LOOKUP_THREADS {
+            let barrier = Arc::clone(&barrier);
+            let expected = expected.clone();
+            handles.push(std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..ROUNDS {
+                    assert_eq!(single().unwrap(), expected);
+                }
+            }));
+        }
+        for _ in 0..BATCH_THREADS {
+            let barrier = Arc::clone(&barrier);
+            let expected_batch = expected_batch.clone();
+            handles.push(std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..ROUNDS {
+                    assert_eq!(batch().unwrap(), expected_batch);
+                }
+            }));
+        }
+        let info = get_info().unwrap();
+        barrier.wait();
+        for _ in 0..ROUNDS {
+            assert_eq!(get_info().unwrap(), info);
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-embedded-data"))]
+    fn contended_first_calls_share_one_cached_value() {
+        use std::sync::{Arc, Barrier};
+
+        const THREADS: usize = 32;
+
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    let known = is_known_postcode("1012AB".to_string()).unwrap();
+                    let unknown = is_known_postcode("0000AA".to_string()).unwrap();
+                    let set = loaded_data().unwrap().known_postcodes.get().unwrap();
+                    (known, unknown, set as *const Set<Vec<u8>> as usize)
+                })
+            })
+            .collect();
+        let seen: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(seen[0].0 && !seen[0].1);
+        assert!(seen.iter().all(|entry| *entry == seen[0]));
+    }
+
+    #[test]
+    fn results_take_their_kind_from_the_queried_dataset() {
+        let mut kinds = b"1234AB".to_vec();
+        kinds.push(1);
+        let compressed =
+            brotli_payload(&[("1234AB|Markt|Utrecht", 0)], &[1, 0, 7, 0], Some(&kinds));
+        let data = parse_data(&compressed, Some(Codec::Brotli), DataOrigin::Buffer).unwrap();
+
+        let result = lookup_address_fst(&data, "1234AB", 7, FIELD_ALL)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.kind, Some(PostcodeKind::Business));
+        let result = lookup_postcode_fst(&data, "1234AB").unwrap().unwrap();
+        assert_eq!(result.kind, Some(PostcodeKind::Business));
+    }
+
+    #[test]
+    fn empty_batches_do_not_load_the_data() {
+        // Other tests load the data into this process
+        if !in_fresh_process("empty_batches_do_not_load_the_data") {
+            return;
+        }
+
+        assert!(lookup_batch(Vec::new(), None).unwrap().is_empty());
+        assert!(contains_batch(Vec::new()).unwrap().is_empty());
+        assert!(lookup_grouped(Vec::new(), None).unwrap().is_empty());
+        assert!(cities_for_postcodes(Vec::new()).unwrap().is_empty());
+        assert!(lookup_binary(Vec::new().into()).unwrap().is_empty());
+        assert!(POSTCODE_DATA.get().is_none());
+    }
+
+    #[test]
+    fn strict_mode_rejects_malformed_keys_on_postcode_only_lookups() {
+        // Installs its own data and turns on strict mode, both process-wide
+        if !in_fresh_process("strict_mode_rejects_malformed_keys_on_postcode_only_lookups") {
+            return;
+        }
+        // "1234AB|Kerkstraat" lacks its city and sorts before the valid key
+        let compressed = brotli_payload(
+            &[("1234AB|Kerkstraat", 0), ("1234AB|Markt|Utrecht", 0)],
+            &[1, 0, 1, 0],
+            None,
+        );
+        install_data(parse_data(&compressed, Some(Codec::Brotli), DataOrigin::Buffer).unwrap())
+            .unwrap();
+
+        let result = lookup("1234AB".to_string(), 0, None).unwrap().unwrap();
+        assert_eq!(result.straat, "Markt");
+        assert_eq!(
+            cities_for_postcode("1234AB".to_string()).unwrap(),
+            ["Utrecht"]
+        );
+
+        set_strict_mode(true);
+        let error = lookup("1234AB".to_string(), 0, None).err().unwrap();
+        assert!(error.reason.starts_with("DataCorrupted: malformed key"));
+        assert!(cities_for_postcode("1234AB".to_string()).is_err());
+        assert!(cities_for_postcodes(vec!["1234AB".to_string()]).is_err());
+    }
 
-        let new_num = last_num + delta;
-        nums.push(new_num);
-        last_num = new_num;
+    #[test]
+    fn dutch_title_case_keeps_articles_and_particles() {
+        assert_eq!(dutch_title_case("'S-GRAVENHAGE"), "'s-Gravenhage");
+        assert_eq!(dutch_title_case("'s-gravenhage"), "'s-Gravenhage");
+        assert_eq!(dutch_title_case("DEN HAAG"), "Den Haag");
+        assert_eq!(dutch_title_case("den haag"), "Den Haag");
+        assert_eq!(
+            dutch_title_case("capelle aan den ijssel"),
+            "Capelle aan den IJssel"
+        );
     }
-    nums
 }